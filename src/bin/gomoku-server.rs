@@ -0,0 +1,18 @@
+use gomoku::server::{Server, DEFAULT_SERVER_ADDRESS};
+use std::net::TcpListener;
+
+fn main() {
+    // The address can be given as the first argument, e.g. `0.0.0.0:8080`
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_SERVER_ADDRESS.to_string());
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("failed to listen on {}: {}", address, error);
+            return;
+        }
+    };
+    println!("listening on http://{}", address);
+    Server::new().serve(listener);
+}
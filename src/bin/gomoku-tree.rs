@@ -0,0 +1,26 @@
+use gomoku::{
+    json::Json,
+    search_tree::{explore, SearchTree, SEARCH_TREE_FILE},
+};
+
+fn main() {
+    // The file can be given as the first argument, the last search of the game is used otherwise
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| SEARCH_TREE_FILE.to_string());
+    let tree = std::fs::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|text| text.parse::<Json>())
+        .and_then(|json| SearchTree::from_json(&json));
+    let tree = match tree {
+        Ok(tree) => tree,
+        Err(error) => {
+            eprintln!("failed to load the search tree {}: {}", path, error);
+            return;
+        }
+    };
+    println!("enter the number of a move, u for the parent, r for the root or q to quit");
+    if let Err(error) = explore(&tree, std::io::stdin().lock(), std::io::stdout()) {
+        eprintln!("{}", error);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     constants::{
-        BOARD_PIECES_USIZE, BOARD_SIZE, BOARD_SIZE_USIZE, DIRECTIONS, OPPOSITE_DIRECTIONS,
+        BOARD_PIECES_USIZE, BOARD_SIZE, BOARD_SIZE_USIZE, DIRECTIONS, OPENING_AREA,
+        OPPOSITE_DIRECTIONS,
     },
     macros::coord,
     patterns::{
@@ -14,7 +15,7 @@ use crate::{
     rules::RuleSet,
 };
 use colored::Colorize;
-use std::{collections::BTreeSet, fmt};
+use std::{collections::BTreeSet, fmt, str::FromStr};
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Coordinates {
@@ -34,6 +35,131 @@ pub struct PossibleMove {
     pub legal: bool,
 }
 
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.x, self.y)
+    }
+}
+
+// Parse the coordinates written by Display, e.g. 9x9
+impl FromStr for Coordinates {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid coordinates {}", text);
+        let (x, y) = text.split_once('x').ok_or_else(error)?;
+        Ok(Coordinates {
+            x: x.parse().map_err(|_| error())?,
+            y: y.parse().map_err(|_| error())?,
+        })
+    }
+}
+
+impl Coordinates {
+    pub fn is_on_board(&self) -> bool {
+        (0..BOARD_SIZE).contains(&self.x) && (0..BOARD_SIZE).contains(&self.y)
+    }
+}
+
+// Reason a movement is refused, with the rocks that are involved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IllegalMove {
+    Occupied,
+    // Each free three that the movement would create, including the movement
+    DoubleFreeThree(Vec<[Coordinates; 3]>),
+    // Rocks of the capture patterns the movement would be captured in, without the movement
+    RecursiveCapture(Vec<Coordinates>),
+    OutOfOpeningArea,
+}
+
+impl IllegalMove {
+    pub fn description(&self) -> &'static str {
+        match self {
+            IllegalMove::Occupied => "Intersection is occupied",
+            IllegalMove::DoubleFreeThree(_) => "Double free three",
+            IllegalMove::RecursiveCapture(_) => "Move into a capture",
+            IllegalMove::OutOfOpeningArea => "Out of the opening area",
+        }
+    }
+
+    // Rocks to highlight to explain the reason
+    pub fn rocks(&self) -> Vec<Coordinates> {
+        match self {
+            IllegalMove::DoubleFreeThree(threes) => threes.iter().flatten().copied().collect(),
+            IllegalMove::RecursiveCapture(rocks) => rocks.clone(),
+            _ => vec![],
+        }
+    }
+}
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description())?;
+        match self {
+            IllegalMove::DoubleFreeThree(threes) => {
+                let threes: Vec<String> = threes
+                    .iter()
+                    .map(|three| format!("{} {} {}", three[0], three[1], three[2]))
+                    .collect();
+                write!(f, ": {}", threes.join(" and "))
+            }
+            IllegalMove::RecursiveCapture(rocks) => {
+                let rocks: Vec<String> = rocks.iter().map(|rock| rock.to_string()).collect();
+                write!(f, ": {}", rocks.join(" "))
+            }
+            IllegalMove::OutOfOpeningArea => write!(
+                f,
+                ": the opening stones are between {} and {}",
+                OPENING_AREA.0, OPENING_AREA.1
+            ),
+            IllegalMove::Occupied => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    FullBoard,
+    // The player to move has no legal intersection left
+    NoLegalMove(Player),
+    MoveLimit(u16),
+    // Both players agreed to a draw
+    Agreement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinReason {
+    FiveInARow,
+    Captures,
+    // The opponent gave up
+    Resignation,
+    // The opponent ran out of time
+    Time,
+}
+
+impl fmt::Display for WinReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WinReason::FiveInARow => write!(f, "five in a row"),
+            WinReason::Captures => write!(f, "by captures"),
+            WinReason::Resignation => write!(f, "by resignation"),
+            WinReason::Time => write!(f, "on time"),
+        }
+    }
+}
+
+impl fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawReason::FullBoard => write!(f, "the board is full"),
+            DrawReason::NoLegalMove(Player::Black) => write!(f, "no legal move for black"),
+            DrawReason::NoLegalMove(Player::White) => write!(f, "no legal move for white"),
+            DrawReason::MoveLimit(limit) => write!(f, "{} moves limit reached", limit),
+            DrawReason::Agreement => write!(f, "agreed by the players"),
+        }
+    }
+}
+
 impl fmt::Display for Move {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.player == Player::Black {
@@ -164,26 +290,51 @@ impl Board {
         false
     }
 
+    pub fn reached_move_limit(&self, rules: &RuleSet) -> bool {
+        rules.move_limit.is_some_and(|limit| self.moves >= limit)
+    }
+
+    // Check if the game is a draw before the given player move
+    // -- this function is called *after* checking the previous move is not winning
+    pub fn draw_reason(&self, rules: &RuleSet, player: Player) -> Option<DrawReason> {
+        if self.all_rocks.len() >= BOARD_PIECES_USIZE {
+            Some(DrawReason::FullBoard)
+        } else if self.reached_move_limit(rules) {
+            rules.move_limit.map(DrawReason::MoveLimit)
+        } else if !self.player_can_play(rules, player) {
+            Some(DrawReason::NoLegalMove(player))
+        } else {
+            None
+        }
+    }
+
     // All open intersections for the current Board
     // -- Empty cases within other pieces
     pub fn open_intersections(&self) -> Vec<Coordinates> {
+        self.open_intersections_within(1)
+    }
+
+    // All open intersections at a distance of radius or less of other pieces
+    pub fn open_intersections_within(&self, radius: i16) -> Vec<Coordinates> {
         // Only the center intersection is available if there is no previous moves
         if self.moves == 0 {
             return vec![coord!(BOARD_SIZE / 2, BOARD_SIZE / 2)];
         }
         let mut intersections: BTreeSet<Coordinates> = BTreeSet::new();
         for existing_rock in self.all_rocks.iter() {
-            for (mov_x, mov_y) in DIRECTIONS {
-                let new_coords = (existing_rock.x + mov_x, existing_rock.y + mov_y);
-                // Check Board boundaries
-                if new_coords.0 >= 0
-                    && new_coords.1 >= 0
-                    && new_coords.0 < BOARD_SIZE
-                    && new_coords.1 < BOARD_SIZE
-                    && self.get(new_coords.0, new_coords.1) == Rock::None
-                {
-                    let new_coords = coord!(new_coords.0, new_coords.1);
-                    intersections.insert(new_coords);
+            for mov_x in -radius..=radius {
+                for mov_y in -radius..=radius {
+                    let new_coords = (existing_rock.x + mov_x, existing_rock.y + mov_y);
+                    // Check Board boundaries
+                    if new_coords.0 >= 0
+                        && new_coords.1 >= 0
+                        && new_coords.0 < BOARD_SIZE
+                        && new_coords.1 < BOARD_SIZE
+                        && self.get(new_coords.0, new_coords.1) == Rock::None
+                    {
+                        let new_coords = coord!(new_coords.0, new_coords.1);
+                        intersections.insert(new_coords);
+                    }
                 }
             }
         }
@@ -340,6 +491,123 @@ impl Board {
         true
     }
 
+    // Rocks of each free three created by the movement
+    // -- found with the same patterns as movement_create_double_free_three
+    pub fn movement_free_threes(
+        &self,
+        coordinates: &Coordinates,
+        player: Player,
+    ) -> Vec<[Coordinates; 3]> {
+        let three = |direction: &(i16, i16), offsets: [i16; 3]| {
+            offsets.map(|offset| {
+                coord!(
+                    coordinates.x + direction.0 * offset,
+                    coordinates.y + direction.1 * offset
+                )
+            })
+        };
+        let mut threes = vec![];
+
+        // [0 {1} 1 {1} 0] and [0 {1} 0 1 1 0]
+        for direction in &DIRECTIONS {
+            if self.check_pattern(coordinates, direction, &FREE_THREE_DIRECT_PATTERN, player) {
+                threes.push(three(direction, [0, 1, 2]));
+            }
+        }
+        for direction in &DIRECTIONS {
+            if self.check_pattern(
+                coordinates,
+                direction,
+                &FREE_THREE_SECONDARY_PATTERN,
+                player,
+            ) {
+                threes.push(three(direction, [0, 2, 3]));
+            }
+        }
+
+        // [0 1 {1} 1 0] and [0 1 {1} 0 1 0], only once for a global direction
+        for (left, right) in &OPPOSITE_DIRECTIONS {
+            if let Some(direction) = [left, right].into_iter().find(|direction| {
+                self.check_pattern(
+                    coordinates,
+                    direction,
+                    &FREE_THREE_DIRECT_CENTER_PATTERN,
+                    player,
+                )
+            }) {
+                threes.push(three(direction, [-1, 0, 1]));
+            }
+        }
+        for (left, right) in &OPPOSITE_DIRECTIONS {
+            if let Some(direction) = [left, right].into_iter().find(|direction| {
+                self.check_pattern(
+                    coordinates,
+                    direction,
+                    &FREE_THREE_SECONDARY_CENTER_PATTERN,
+                    player,
+                )
+            }) {
+                threes.push(three(direction, [-1, 0, 2]));
+            }
+        }
+
+        threes
+    }
+
+    // Rocks of each [2 1 0 2] pattern the movement would create, without the movement
+    pub fn movement_recursive_capture_rocks(
+        &self,
+        coordinates: &Coordinates,
+        player: Player,
+    ) -> Vec<Coordinates> {
+        let mut rocks = vec![];
+        for direction in &DIRECTIONS {
+            if self.check_pattern(coordinates, direction, RECURSIVE_CAPTURE_PATTERN, player) {
+                for (offset, _) in RECURSIVE_CAPTURE_PATTERN {
+                    rocks.push(coord!(
+                        coordinates.x + direction.0 * offset,
+                        coordinates.y + direction.1 * offset
+                    ));
+                }
+            }
+        }
+        rocks
+    }
+
+    // Same checks as is_move_legal, but explain why a movement is refused
+    pub fn illegal_move_reason(&self, rules: &RuleSet, movement: &Move) -> Option<IllegalMove> {
+        let coordinates = &movement.coordinates;
+        if self.get(coordinates.x, coordinates.y) != Rock::None {
+            return Some(IllegalMove::Occupied);
+        }
+        if rules.no_double_three {
+            let threes = self.movement_free_threes(coordinates, movement.player);
+            if threes.len() >= 2 {
+                return Some(IllegalMove::DoubleFreeThree(threes));
+            }
+        }
+        if rules.capture {
+            let rocks = self.movement_recursive_capture_rocks(coordinates, movement.player);
+            if !rocks.is_empty() {
+                return Some(IllegalMove::RecursiveCapture(rocks));
+            }
+        }
+        None
+    }
+
+    // Opening stones also need to be in the opening area
+    pub fn illegal_opening_move_reason(
+        &self,
+        rules: &RuleSet,
+        movement: &Move,
+    ) -> Option<IllegalMove> {
+        let area = OPENING_AREA.0..=OPENING_AREA.1;
+        if !area.contains(&movement.coordinates.x) || !area.contains(&movement.coordinates.y) {
+            return Some(IllegalMove::OutOfOpeningArea);
+        }
+        self.illegal_move_reason(rules, movement)
+    }
+
     // Check if a move *can* be executed according to the rules
     pub fn is_move_legal(&self, rules: &RuleSet, movement: &Move) -> bool {
         self.coordinates_are_legal(rules, &movement.coordinates, movement.player)
@@ -347,9 +615,19 @@ impl Board {
 
     // All *legal* possible movements from the intersections for a given player
     pub fn intersections_legal_moves(&self, rules: &RuleSet, player: Player) -> Vec<Move> {
+        self.intersections_legal_moves_within(rules, player, 1)
+    }
+
+    // All *legal* possible movements from the intersections within radius for a given player
+    pub fn intersections_legal_moves_within(
+        &self,
+        rules: &RuleSet,
+        player: Player,
+        radius: i16,
+    ) -> Vec<Move> {
         // Analyze each intersections and check if a Rock can be set on it
         // -- for the current player according to the rules
-        let intersections = self.open_intersections();
+        let intersections = self.open_intersections_within(radius);
         let mut moves: Vec<Move> = vec![];
         moves.reserve(intersections.len());
         for coordinates in intersections.iter() {
@@ -665,16 +943,22 @@ impl Board {
     // (Has an unbreakable winning position according to the rules)
     // This function is called *after* a move is made, so the [0] is already on the board
     pub fn is_winning(&self, rules: &RuleSet, player: Player) -> bool {
+        self.win_reason(rules, player).is_some()
+    }
+
+    // How the player won on the board, if it did
+    pub fn win_reason(&self, rules: &RuleSet, player: Player) -> Option<WinReason> {
         if rules.capture
             && ((player == Player::Black && self.black.captures >= 10)
                 || (player == Player::White && self.white.captures >= 10))
         {
-            return true;
+            return Some(WinReason::Captures);
         }
-        if rules.game_ending_capture {
+        let five_in_a_row = if rules.game_ending_capture {
             self.has_uncaptured_five_in_a_row(rules, player)
         } else {
             self.has_five_in_a_row(player)
-        }
+        };
+        five_in_a_row.then_some(WinReason::FiveInARow)
     }
 }
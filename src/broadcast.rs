@@ -0,0 +1,470 @@
+use crate::{
+    board::{DrawReason, Move, WinReason},
+    clock::TimeControl,
+    game::Winner,
+    player::Player,
+    record::{move_text, parse_move},
+    rules::RuleSet,
+};
+use std::{
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader, ErrorKind, Write},
+    net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+pub const DEFAULT_BROADCAST_ADDRESS: &str = "127.0.0.1:4343";
+// Streams starting with this prefix are written to or followed in a file
+const FILE_PREFIX: &str = "file:";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// Delay before checking again for new lines at the end of a followed file
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(100);
+
+// One event by line, e.g. `move B 9x9` or `clock 295000 0 298500 0`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    // A new game, the spectators start again from an empty board
+    Start {
+        rules: RuleSet,
+        time_control: TimeControl,
+    },
+    Move(Move),
+    // Score and expected line of the computer, sent before its move
+    Evaluation {
+        score: i32,
+        mate_in: Option<i32>,
+        line: Vec<Move>,
+    },
+    // Time left of each player when the turn changes, indexed by player
+    Clock {
+        times: [Duration; 2],
+        periods: [u32; 2],
+    },
+    Undo,
+    End(Winner),
+}
+
+fn win_reason_text(reason: WinReason) -> &'static str {
+    match reason {
+        WinReason::FiveInARow => "five",
+        WinReason::Captures => "captures",
+        WinReason::Resignation => "resignation",
+        WinReason::Time => "time",
+    }
+}
+
+fn parse_win_reason(text: &str) -> Option<WinReason> {
+    match text {
+        "five" => Some(WinReason::FiveInARow),
+        "captures" => Some(WinReason::Captures),
+        "resignation" => Some(WinReason::Resignation),
+        "time" => Some(WinReason::Time),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Start {
+                rules,
+                time_control,
+            } => write!(
+                f,
+                "start {} {} {} {} {}",
+                rules.capture,
+                rules.game_ending_capture,
+                rules.no_double_three,
+                rules
+                    .move_limit
+                    .map_or("-".to_string(), |limit| limit.to_string()),
+                time_control.argument()
+            ),
+            Event::Move(movement) => write!(f, "move {}", move_text(movement)),
+            Event::Evaluation {
+                score,
+                mate_in,
+                line,
+            } => {
+                let line: Vec<String> = line.iter().map(move_text).collect();
+                write!(
+                    f,
+                    "eval {} {} {}",
+                    score,
+                    mate_in.map_or("-".to_string(), |mate_in| mate_in.to_string()),
+                    line.join(", ")
+                )
+            }
+            Event::Clock { times, periods } => write!(
+                f,
+                "clock {} {} {} {}",
+                times[Player::Black as usize].as_millis(),
+                periods[Player::Black as usize],
+                times[Player::White as usize].as_millis(),
+                periods[Player::White as usize]
+            ),
+            Event::Undo => write!(f, "undo"),
+            Event::End(winner) => match winner {
+                Winner::None => write!(f, "end none"),
+                Winner::Black(reason) => write!(f, "end black {}", win_reason_text(*reason)),
+                Winner::White(reason) => write!(f, "end white {}", win_reason_text(*reason)),
+                Winner::Draw(DrawReason::FullBoard) => write!(f, "end draw full"),
+                Winner::Draw(DrawReason::NoLegalMove(player)) => write!(
+                    f,
+                    "end draw nolegal {}",
+                    if *player == Player::Black { "B" } else { "W" }
+                ),
+                Winner::Draw(DrawReason::MoveLimit(limit)) => write!(f, "end draw limit {}", limit),
+                Winner::Draw(DrawReason::Agreement) => write!(f, "end draw agreement"),
+            },
+        }
+    }
+}
+
+impl FromStr for Event {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid event {}", line);
+        let (name, arguments) = line.split_once(' ').unwrap_or((line, ""));
+        match name {
+            "start" => match arguments.split(' ').collect::<Vec<_>>()[..] {
+                [capture, game_ending_capture, no_double_three, move_limit, time_control] => {
+                    Ok(Event::Start {
+                        rules: RuleSet {
+                            capture: capture.parse().map_err(|_| error())?,
+                            game_ending_capture: game_ending_capture
+                                .parse()
+                                .map_err(|_| error())?,
+                            no_double_three: no_double_three.parse().map_err(|_| error())?,
+                            move_limit: match move_limit {
+                                "-" => None,
+                                limit => Some(limit.parse().map_err(|_| error())?),
+                            },
+                        },
+                        time_control: time_control.parse()?,
+                    })
+                }
+                _ => Err(error()),
+            },
+            "move" => Ok(Event::Move(parse_move(arguments)?)),
+            "eval" => {
+                let mut parts = arguments.splitn(3, ' ');
+                let (Some(score), Some(mate_in)) = (parts.next(), parts.next()) else {
+                    return Err(error());
+                };
+                Ok(Event::Evaluation {
+                    score: score.parse().map_err(|_| error())?,
+                    mate_in: match mate_in {
+                        "-" => None,
+                        mate_in => Some(mate_in.parse().map_err(|_| error())?),
+                    },
+                    line: parts
+                        .next()
+                        .unwrap_or_default()
+                        .split(", ")
+                        .filter(|movement| !movement.is_empty())
+                        .map(parse_move)
+                        .collect::<Result<_, _>>()?,
+                })
+            }
+            "clock" => {
+                let values = arguments
+                    .split(' ')
+                    .map(|value| value.parse::<u64>().map_err(|_| error()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match values[..] {
+                    [black, black_periods, white, white_periods] => Ok(Event::Clock {
+                        times: [Duration::from_millis(black), Duration::from_millis(white)],
+                        periods: [black_periods as u32, white_periods as u32],
+                    }),
+                    _ => Err(error()),
+                }
+            }
+            "undo" if arguments.is_empty() => Ok(Event::Undo),
+            "end" => Ok(Event::End(match arguments.split_once(' ') {
+                Some(("black", reason)) => {
+                    Winner::Black(parse_win_reason(reason).ok_or_else(error)?)
+                }
+                Some(("white", reason)) => {
+                    Winner::White(parse_win_reason(reason).ok_or_else(error)?)
+                }
+                _ => match arguments {
+                    "none" => Winner::None,
+                    "draw full" => Winner::Draw(DrawReason::FullBoard),
+                    "draw nolegal B" => Winner::Draw(DrawReason::NoLegalMove(Player::Black)),
+                    "draw nolegal W" => Winner::Draw(DrawReason::NoLegalMove(Player::White)),
+                    "draw agreement" => Winner::Draw(DrawReason::Agreement),
+                    arguments => match arguments.strip_prefix("draw limit ") {
+                        Some(limit) => {
+                            Winner::Draw(DrawReason::MoveLimit(limit.parse().map_err(|_| error())?))
+                        }
+                        None => return Err(error()),
+                    },
+                },
+            })),
+            _ => Err(error()),
+        }
+    }
+}
+
+enum Output {
+    // Spectators connect to the listener at any time
+    Socket {
+        listener: TcpListener,
+        spectators: Vec<TcpStream>,
+    },
+    File(File),
+}
+
+// Stream of the events of the games played by this instance
+pub struct Broadcaster {
+    output: Option<Output>,
+    // Events of the current game, sent first to the spectators that connect during it
+    history: Vec<Event>,
+    pub error: Option<String>,
+}
+
+impl Broadcaster {
+    // Listen on an address, or write to a file with `file:path`
+    // -- a failure is kept as the error of the broadcast
+    pub fn start(target: &str) -> Broadcaster {
+        let output = if let Some(path) = target.strip_prefix(FILE_PREFIX) {
+            File::create(path).map(Output::File)
+        } else {
+            TcpListener::bind(target).and_then(|listener| {
+                listener.set_nonblocking(true)?;
+                Ok(Output::Socket {
+                    listener,
+                    spectators: vec![],
+                })
+            })
+        };
+        match output {
+            Ok(output) => Broadcaster {
+                output: Some(output),
+                history: vec![],
+                error: None,
+            },
+            Err(error) => Broadcaster {
+                output: None,
+                history: vec![],
+                error: Some(format!("{}: {}", target, error)),
+            },
+        }
+    }
+
+    // Address the spectators can connect to, with the port chosen by the system if it was 0
+    pub fn local_address(&self) -> Option<String> {
+        match &self.output {
+            Some(Output::Socket { listener, .. }) => listener
+                .local_addr()
+                .ok()
+                .map(|address| address.to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn spectators(&self) -> usize {
+        match &self.output {
+            Some(Output::Socket { spectators, .. }) => spectators.len(),
+            _ => 0,
+        }
+    }
+
+    // Accept the new spectators and send them the events of the current game
+    pub fn accept(&mut self) {
+        let Some(Output::Socket {
+            listener,
+            spectators,
+        }) = &mut self.output
+        else {
+            return;
+        };
+        loop {
+            match listener.accept() {
+                Ok((mut stream, _)) => {
+                    let _ = stream.set_nonblocking(false);
+                    let _ = stream.set_nodelay(true);
+                    if self
+                        .history
+                        .iter()
+                        .try_for_each(|event| writeln!(stream, "{}", event))
+                        .is_ok()
+                    {
+                        spectators.push(stream);
+                    }
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return,
+                Err(error) => {
+                    self.error = Some(error.to_string());
+                    return;
+                }
+            }
+        }
+    }
+
+    // The spectators that can't be written to are dropped
+    pub fn send(&mut self, event: Event) {
+        self.accept();
+        if let Event::Start { .. } = event {
+            self.history.clear();
+        }
+        match &mut self.output {
+            Some(Output::Socket { spectators, .. }) => {
+                spectators.retain_mut(|stream| writeln!(stream, "{}", event).is_ok());
+            }
+            Some(Output::File(file)) => {
+                if let Err(error) = writeln!(file, "{}", event).and_then(|_| file.flush()) {
+                    self.output = None;
+                    self.error = Some(error.to_string());
+                }
+            }
+            None => {}
+        }
+        self.history.push(event);
+    }
+}
+
+// Follow the events broadcasted by another instance, read on another thread to never block the game
+pub struct Spectator {
+    // Kept to close the connection
+    stream: Option<TcpStream>,
+    events: Receiver<Result<Event, String>>,
+    stop: Arc<AtomicBool>,
+    // Last evaluation of the computer that is displayed
+    pub score: Option<i32>,
+    pub error: Option<String>,
+}
+
+// Send the lines to the spectator, until it's dropped or a line is invalid
+fn send_line(sender: &Sender<Result<Event, String>>, line: &str) -> bool {
+    let event = line.trim_end().parse();
+    let failed = event.is_err();
+    sender.send(event).is_ok() && !failed
+}
+
+impl Spectator {
+    // Connect to an address, or follow a file with `file:path`
+    pub fn watch(target: &str) -> Spectator {
+        let (sender, events) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut spectator = Spectator {
+            stream: None,
+            events,
+            stop: stop.clone(),
+            score: None,
+            error: None,
+        };
+        if let Some(path) = target.strip_prefix(FILE_PREFIX) {
+            match File::open(path) {
+                Ok(file) => {
+                    thread::spawn(move || Spectator::follow(file, sender, stop));
+                }
+                Err(error) => spectator.error = Some(format!("{}: {}", target, error)),
+            }
+            return spectator;
+        }
+        let stream = target
+            .to_socket_addrs()
+            .map_err(|error| error.to_string())
+            .and_then(|mut addresses| {
+                addresses
+                    .next()
+                    .ok_or_else(|| format!("Unknown address {}", target))
+            })
+            .and_then(|address| {
+                TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+                    .map_err(|error| error.to_string())
+            });
+        let reader = stream.and_then(|stream| {
+            let reader = stream.try_clone().map_err(|error| error.to_string())?;
+            spectator.stream = Some(stream);
+            Ok(reader)
+        });
+        match reader {
+            Ok(reader) => {
+                thread::spawn(move || {
+                    for line in BufReader::new(reader).lines() {
+                        let sent = match line {
+                            Ok(line) => send_line(&sender, &line),
+                            Err(error) => {
+                                let _ = sender.send(Err(error.to_string()));
+                                false
+                            }
+                        };
+                        if !sent {
+                            return;
+                        }
+                    }
+                    let _ = sender.send(Err("Broadcast ended".to_string()));
+                });
+            }
+            Err(error) => spectator.error = Some(error),
+        }
+        spectator
+    }
+
+    // Read the lines written to the file, and wait at its end for the next ones
+    fn follow(file: File, sender: Sender<Result<Event, String>>, stop: Arc<AtomicBool>) {
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        while !stop.load(Ordering::Relaxed) {
+            match reader.read_line(&mut line) {
+                // A line without its end is completed on the next read
+                Ok(_) if !line.ends_with('\n') => thread::sleep(FOLLOW_INTERVAL),
+                Ok(_) => {
+                    if !send_line(&sender, &line) {
+                        return;
+                    }
+                    line.clear();
+                }
+                Err(error) => {
+                    let _ = sender.send(Err(error.to_string()));
+                    return;
+                }
+            }
+        }
+    }
+
+    // Close the connection or stop reading the file, the error is displayed instead
+    pub fn stop(&mut self, error: String) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(stream) = self.stream.take() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        self.error = Some(error);
+    }
+
+    // All the events received since the last call, with the error that ended the stream
+    pub fn receive(&self) -> (Vec<Event>, Option<String>) {
+        let mut events = vec![];
+        loop {
+            match self.events.try_recv() {
+                Ok(Ok(event)) => events.push(event),
+                Ok(Err(error)) => return (events, Some(error)),
+                Err(TryRecvError::Empty) => return (events, None),
+                Err(TryRecvError::Disconnected) => {
+                    return (events, Some("Broadcast ended".to_string()))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Spectator {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(stream) = &self.stream {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+}
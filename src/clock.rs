@@ -0,0 +1,293 @@
+use crate::player::Player;
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+// The computer plans its time as if this number of moves were left to play
+const MOVES_TO_GO: u32 = 30;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeControl {
+    #[default]
+    Unlimited,
+    // The player loses when its time is over
+    SuddenDeath(Duration),
+    // The increment is added after each move
+    Fischer {
+        time: Duration,
+        increment: Duration,
+    },
+    // After the main time, each move must be played in a period or the period is lost
+    ByoYomi {
+        time: Duration,
+        periods: u32,
+        period: Duration,
+    },
+}
+
+impl TimeControl {
+    fn main_time(&self) -> Duration {
+        match *self {
+            TimeControl::Unlimited => Duration::ZERO,
+            TimeControl::SuddenDeath(time) => time,
+            TimeControl::Fischer { time, .. } => time,
+            TimeControl::ByoYomi { time, .. } => time,
+        }
+    }
+
+    // Text that is parsed back to the same time control
+    pub fn argument(&self) -> String {
+        match *self {
+            TimeControl::Unlimited => "unlimited".to_string(),
+            TimeControl::SuddenDeath(time) => format!("sudden:{}", time.as_secs()),
+            TimeControl::Fischer { time, increment } => {
+                format!("fischer:{}+{}", time.as_secs(), increment.as_secs())
+            }
+            TimeControl::ByoYomi {
+                time,
+                periods,
+                period,
+            } => format!(
+                "byoyomi:{}+{}x{}",
+                time.as_secs(),
+                periods,
+                period.as_secs()
+            ),
+        }
+    }
+}
+
+// Parse the time controls given to the command line, in seconds
+// -- unlimited, sudden:300, fischer:180+2 or byoyomi:300+5x30
+impl FromStr for TimeControl {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Unknown time control {}", text);
+        let seconds = |value: &str| value.parse::<u64>().map(Duration::from_secs);
+        let (name, value) = text.split_once(':').unwrap_or((text, ""));
+        match name.to_lowercase().as_str() {
+            "unlimited" | "none" => Ok(TimeControl::Unlimited),
+            "sudden" => Ok(TimeControl::SuddenDeath(
+                seconds(value).map_err(|_| error())?,
+            )),
+            "fischer" => {
+                let (time, increment) = value.split_once('+').ok_or_else(error)?;
+                Ok(TimeControl::Fischer {
+                    time: seconds(time).map_err(|_| error())?,
+                    increment: seconds(increment).map_err(|_| error())?,
+                })
+            }
+            "byoyomi" => {
+                let (time, byo_yomi) = value.split_once('+').ok_or_else(error)?;
+                let (periods, period) = byo_yomi.split_once('x').ok_or_else(error)?;
+                Ok(TimeControl::ByoYomi {
+                    time: seconds(time).map_err(|_| error())?,
+                    periods: periods.parse().map_err(|_| error())?,
+                    period: seconds(period).map_err(|_| error())?,
+                })
+            }
+            _ => Err(error()),
+        }
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeControl::Unlimited => write!(f, "unlimited"),
+            TimeControl::SuddenDeath(time) => write!(f, "sudden death {}", format_time(*time)),
+            TimeControl::Fischer { time, increment } => {
+                write!(f, "{} +{}s", format_time(*time), increment.as_secs())
+            }
+            TimeControl::ByoYomi {
+                time,
+                periods,
+                period,
+            } => write!(
+                f,
+                "{} +{}x{}s",
+                format_time(*time),
+                periods,
+                period.as_secs()
+            ),
+        }
+    }
+}
+
+// Minutes and seconds, with the tenths in the last ten seconds
+pub fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    if seconds < 10 {
+        format!("0:0{}.{}", seconds, time.subsec_millis() / 100)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerTime {
+    // Main time left, also the Fischer increments
+    pub main: Duration,
+    // Byo-yomi periods left
+    pub periods: u32,
+    pub flagged: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Clock {
+    pub control: TimeControl,
+    // Indexed by player
+    pub times: [PlayerTime; 2],
+    // Player that is thinking and since when
+    turn: Option<(Player, Instant)>,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock::new(TimeControl::Unlimited)
+    }
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Clock {
+        let time = PlayerTime {
+            main: control.main_time(),
+            periods: match control {
+                TimeControl::ByoYomi { periods, .. } => periods,
+                _ => 0,
+            },
+            flagged: false,
+        };
+        Clock {
+            control,
+            times: [time, time],
+            turn: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.control != TimeControl::Unlimited
+    }
+
+    pub fn turn_player(&self) -> Option<Player> {
+        self.turn.map(|(player, _)| player)
+    }
+
+    pub fn start_turn(&mut self, player: Player) {
+        self.turn = Some((player, Instant::now()));
+    }
+
+    // Stop the clock of the player that was thinking, returns the player if it lost on time
+    pub fn end_turn(&mut self) -> Option<Player> {
+        let (player, started) = self.turn.take()?;
+        if self.spend(player, started.elapsed()) {
+            None
+        } else {
+            Some(player)
+        }
+    }
+
+    // Remove the time used by the player for a move, returns false if it lost on time
+    pub fn spend(&mut self, player: Player, elapsed: Duration) -> bool {
+        if !self.use_time(player, elapsed) {
+            return false;
+        }
+        if let TimeControl::Fischer { increment, .. } = self.control {
+            self.times[player as usize].main += increment;
+        }
+        true
+    }
+
+    fn use_time(&mut self, player: Player, elapsed: Duration) -> bool {
+        let control = self.control;
+        let time = &mut self.times[player as usize];
+        if control == TimeControl::Unlimited || time.flagged {
+            return !time.flagged;
+        }
+        if elapsed < time.main {
+            time.main -= elapsed;
+            return true;
+        }
+        let overtime = elapsed - time.main;
+        time.main = Duration::ZERO;
+        if let TimeControl::ByoYomi { period, .. } = control {
+            // Each period that is completely used is lost
+            let lost_periods = (overtime.as_millis() / period.as_millis().max(1)) as u32;
+            if lost_periods < time.periods {
+                time.periods -= lost_periods;
+                return true;
+            }
+            time.periods = 0;
+        }
+        time.flagged = true;
+        false
+    }
+
+    // Time of the player, with the time used since the start of its turn
+    pub fn player_time(&self, player: Player) -> PlayerTime {
+        let mut clock = self.clone();
+        if let Some((thinking, started)) = self.turn {
+            if thinking == player {
+                clock.use_time(player, started.elapsed());
+            }
+        }
+        clock.times[player as usize]
+    }
+
+    pub fn is_flagged(&self, player: Player) -> bool {
+        self.player_time(player).flagged
+    }
+
+    // Time left to play before losing, the next byo-yomi period is only counted once
+    fn time_before_loss(&self, time: &PlayerTime) -> Duration {
+        match self.control {
+            TimeControl::ByoYomi { period, .. } if time.periods > 0 => time.main + period,
+            _ => time.main,
+        }
+    }
+
+    // Time the computer can use to search its next move, None when the time is unlimited
+    // -- never more than half of the time left before losing on time
+    pub fn search_budget(&self, player: Player) -> Option<Duration> {
+        let time = &self.times[player as usize];
+        let budget = match self.control {
+            TimeControl::Unlimited => return None,
+            TimeControl::SuddenDeath(_) => time.main / MOVES_TO_GO,
+            TimeControl::Fischer { increment, .. } => time.main / MOVES_TO_GO + increment * 3 / 4,
+            TimeControl::ByoYomi { period, .. } => time.main / MOVES_TO_GO + period * 3 / 4,
+        };
+        Some(budget.min(self.time_before_loss(time) / 2))
+    }
+
+    // Time left of the player for the panel, with the byo-yomi periods
+    pub fn time_text(&self, player: Player) -> String {
+        let time = self.player_time(player);
+        match self.control {
+            TimeControl::ByoYomi { period, .. } if time.main.is_zero() => {
+                // Countdown of the current period when the player is thinking in overtime
+                let period_left = match self.turn {
+                    Some((thinking, started)) if thinking == player => {
+                        let overtime = started
+                            .elapsed()
+                            .saturating_sub(self.times[player as usize].main);
+                        period.saturating_sub(Duration::from_millis(
+                            (overtime.as_millis() % period.as_millis().max(1)) as u64,
+                        ))
+                    }
+                    _ => period,
+                };
+                format!("{} ({} left)", format_time(period_left), time.periods)
+            }
+            TimeControl::ByoYomi { period, .. } => format!(
+                "{} +{}x{}s",
+                format_time(time.main),
+                time.periods,
+                period.as_secs()
+            ),
+            _ => format_time(time.main),
+        }
+    }
+}
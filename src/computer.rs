@@ -1,39 +1,227 @@
 use crate::{
-    board::{Board, Move},
-    constants::DEPTH,
+    board::{Board, Coordinates, Move},
+    constants::{BOARD_SIZE_USIZE, MATE_SCORE, WIN_SCORE},
     heuristic::HEURISTIC,
     patterns::PatternCount,
     player::Player,
     rules::RuleSet,
+    search_tree::SearchTree,
 };
 use colored::Colorize;
-use std::{cmp::Ordering, collections::BinaryHeap, fmt, time::Instant};
+use rand::Rng;
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fmt,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Algorithm {
     Negamax,
     Minimax,
     Greedy,
+    PrincipalVariation,
+    MonteCarlo,
+}
+
+impl Algorithm {
+    // In the same order as the options combo box
+    pub const ALL: [Algorithm; 5] = [
+        Algorithm::Negamax,
+        Algorithm::Minimax,
+        Algorithm::Greedy,
+        Algorithm::PrincipalVariation,
+        Algorithm::MonteCarlo,
+    ];
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "negamax" => Ok(Algorithm::Negamax),
+            "minimax" => Ok(Algorithm::Minimax),
+            "greedy" => Ok(Algorithm::Greedy),
+            "pvs" | "principal_variation" => Ok(Algorithm::PrincipalVariation),
+            "mcts" | "monte_carlo" => Ok(Algorithm::MonteCarlo),
+            _ => Err(format!("Unknown algorithm {}", name)),
+        }
+    }
+}
+
+// Tie-breakers used when two moves have the same best pattern
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveOrdering {
+    Patterns,
+    Killers,
+    History,
+    #[default]
+    KillersAndHistory,
+}
+
+impl MoveOrdering {
+    pub const ALL: [MoveOrdering; 4] = [
+        MoveOrdering::Patterns,
+        MoveOrdering::Killers,
+        MoveOrdering::History,
+        MoveOrdering::KillersAndHistory,
+    ];
+
+    fn use_killers(&self) -> bool {
+        *self == MoveOrdering::Killers || *self == MoveOrdering::KillersAndHistory
+    }
+
+    fn use_history(&self) -> bool {
+        *self == MoveOrdering::History || *self == MoveOrdering::KillersAndHistory
+    }
+}
+
+// Options that change which moves are searched and in which order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub ordering: MoveOrdering,
+    // Distance to the other rocks of the generated moves
+    pub radius: i16,
+    // Only search the moves that answer a four or an open three when there is one
+    pub threats_only: bool,
+    // Maximum number of moves searched for each ply, 0 to search all of them
+    pub beam_width: usize,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            ordering: MoveOrdering::default(),
+            radius: 1,
+            threats_only: false,
+            beam_width: 0,
+        }
+    }
+}
+
+impl fmt::Display for SearchOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ordering {:?} radius {} beam {}{}",
+            self.ordering,
+            self.radius,
+            if self.beam_width == 0 {
+                "all".to_string()
+            } else {
+                self.beam_width.to_string()
+            },
+            if self.threats_only {
+                " threats only"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+// Number of strength levels of the computer in the PvA games
+pub const STRENGTH_LEVELS: usize = 10;
+// Depth of the search of each candidate when the computer chooses a sub-optimal move
+const RANDOMNESS_DEPTH: usize = 2;
+
+// Deliberate sub-optimal choices of the weaker strength levels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Randomness {
+    // Chance to replace the searched move by one of the best candidates
+    pub chance: f64,
+    // Number of moves of the root, in the heuristic order, that can be chosen
+    pub candidates: usize,
+    // A candidate is e times less likely to be chosen for each temperature it's below the best one
+    pub temperature: f64,
+}
+
+// Search limits and mistakes of a strength level of the computer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Strength {
+    pub depth: usize,
+    // The best move found so far is played after this time
+    pub time: Duration,
+    pub beam_width: usize,
+    pub randomness: Option<Randomness>,
 }
 
+impl Strength {
+    // From 1, the weakest, to STRENGTH_LEVELS
+    pub fn level(level: usize) -> Strength {
+        let index = level.clamp(1, STRENGTH_LEVELS) - 1;
+        let (chance, temperature) = [
+            (0.6, 20000.),
+            (0.5, 15000.),
+            (0.4, 12000.),
+            (0.35, 10000.),
+            (0.3, 8000.),
+            (0.2, 6000.),
+            (0.15, 4000.),
+            (0.1, 3000.),
+            (0., 0.),
+            (0., 0.),
+        ][index];
+        Strength {
+            depth: [1, 1, 2, 2, 2, 3, 3, 4, 4, 5][index],
+            time: Duration::from_millis(
+                [250, 250, 500, 500, 750, 1000, 1500, 2000, 3000, 5000][index],
+            ),
+            beam_width: [3, 4, 5, 6, 8, 10, 12, 15, 20, 20][index],
+            randomness: (chance > 0.).then_some(Randomness {
+                chance,
+                candidates: 5,
+                temperature,
+            }),
+        }
+    }
+}
+
+// Half width of the window searched around the score of the previous iteration
+const ASPIRATION_WINDOW: i32 = 25000;
+
+// Number of Monte Carlo playouts for each level of depth
+const MONTE_CARLO_PLAYOUTS: usize = 150;
+// Moves played in a playout before it's counted as a draw
+const MONTE_CARLO_ROLLOUT_DEPTH: usize = 8;
+// A playout randomly selects one of the best moves from the heuristic
+const MONTE_CARLO_ROLLOUT_CANDIDATES: usize = 3;
+// Exploration constant of the UCT formula
+const MONTE_CARLO_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+// Bonus over any history score for the killer moves of a ply
+const KILLER_BONUS: [u32; 2] = [u32::MAX, u32::MAX - 1];
+
 #[derive(Debug, Clone)]
 pub struct SortedMove {
     pub movement: Move,
     pub pattern_count: PatternCount,
     pub best_pattern: u8,
+    // Killer and history score of the move
+    pub order: u32,
 }
 
 impl Eq for SortedMove {}
 
 impl PartialEq for SortedMove {
     fn eq(&self, other: &Self) -> bool {
-        self.best_pattern == other.best_pattern
+        self.best_pattern == other.best_pattern && self.order == other.order
     }
 }
 
 impl Ord for SortedMove {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.best_pattern.cmp(&other.best_pattern)
+        self.best_pattern
+            .cmp(&other.best_pattern)
+            .then(self.order.cmp(&other.order))
     }
 }
 
@@ -43,22 +231,185 @@ impl PartialOrd for SortedMove {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Default, Debug, Clone)]
+pub struct SearchStatistics {
+    // Depth requested for the search
+    pub depth: usize,
+    pub nodes: u64,
+    pub leaf_evaluations: u64,
+    // Number of branches skipped by an alpha-beta cutoff
+    pub cutoffs: u64,
+    // Deepest ply that was reached, can be lower than depth on winning moves
+    pub max_depth: usize,
+    // Depth and time of each completed search of the root
+    pub iterations: Vec<(usize, Duration)>,
+    pub duration: Duration,
+}
+
+impl SearchStatistics {
+    // Branching factor that would visit the same amount of nodes in a uniform tree
+    pub fn effective_branching_factor(&self) -> f64 {
+        if self.max_depth == 0 {
+            return 0.;
+        }
+        (self.nodes as f64).powf(1. / self.max_depth as f64)
+    }
+
+    pub fn nodes_per_second(&self) -> f64 {
+        let seconds = self.duration.as_secs_f64();
+        if seconds == 0. {
+            return 0.;
+        }
+        self.nodes as f64 / seconds
+    }
+}
+
+impl fmt::Display for SearchStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} leaves, {} cutoffs, depth {}/{}, ebf {:.2}, {:.0} nodes/s",
+            self.nodes,
+            self.leaf_evaluations,
+            self.cutoffs,
+            self.max_depth,
+            self.depth,
+            self.effective_branching_factor(),
+            self.nodes_per_second()
+        )?;
+        for (depth, duration) in &self.iterations {
+            write!(f, ", iteration {} in {}ms", depth, duration.as_millis())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default, Debug, Clone)]
 pub struct Evaluation {
     pub score: i32,
     pub movements: Vec<Move>,
+    // Number of playouts that went through each movement, only for the Monte Carlo search
+    pub visits: Vec<u32>,
+    pub statistics: SearchStatistics,
+}
+
+// Number of moves of the player to win for a forced win, or of the opponent for a forced loss (negative)
+pub fn mate_in(score: i32) -> Option<i32> {
+    if score.abs() <= MATE_SCORE {
+        return None;
+    }
+    let plies = WIN_SCORE - score.abs();
+    if score > 0 {
+        Some((plies + 1) / 2)
+    } else {
+        Some(-(plies / 2))
+    }
+}
+
+impl Evaluation {
+    pub fn mate_in(&self) -> Option<i32> {
+        mate_in(self.score)
+    }
 }
 
 impl fmt::Display for Evaluation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(movement) = self.movements.first() {
-            write!(f, "score {} movement {}", self.score, movement)
+            write!(f, "score {} movement {}", self.score, movement)?;
+            match self.mate_in() {
+                Some(moves) if moves > 0 => write!(f, " (win in {})", moves)?,
+                Some(moves) => write!(f, " (loss in {})", -moves)?,
+                None => (),
+            }
+            if let Some(visits) = self.visits.first() {
+                write!(f, " ({} visits)", visits)?;
+            }
+            Ok(())
         } else {
             write!(f, "score {} {}", self.score, "without movement !".red())
         }
     }
 }
 
+// Shared between a running search and the thread that started it
+// -- to stop the search early and to read the best line found so far
+#[derive(Default, Debug, Clone)]
+pub struct SearchControl {
+    stop: Arc<AtomicBool>,
+    best: Arc<Mutex<Option<Evaluation>>>,
+}
+
+impl SearchControl {
+    pub fn stop(&self) {
+        self.stop.store(true, AtomicOrdering::Relaxed);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(AtomicOrdering::Relaxed)
+    }
+
+    pub fn best(&self) -> Option<Evaluation> {
+        self.best.lock().unwrap().clone()
+    }
+
+    fn publish(&self, evaluation: &Evaluation) {
+        *self.best.lock().unwrap() = Some(evaluation.clone());
+    }
+}
+
+// A Computer play running on another thread
+pub struct BackgroundPlay {
+    pub control: SearchControl,
+    pub started: Instant,
+    handle: JoinHandle<(Computer, Result<Evaluation, String>)>,
+}
+
+impl BackgroundPlay {
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    // Stop the search, it will return the best move found so far
+    pub fn move_now(&self) {
+        self.control.stop();
+    }
+
+    // Wait for the search to end and give back the Computer that played
+    pub fn join(self) -> (Computer, Result<Evaluation, String>) {
+        let (mut computer, play_result) = self.handle.join().unwrap_or_else(|_| {
+            (
+                Computer::default(),
+                Err("Computer thread panicked".to_string()),
+            )
+        });
+        computer.control = SearchControl::default();
+        (computer, play_result)
+    }
+}
+
+// An analysis running on another thread
+pub struct BackgroundAnalysis {
+    pub control: SearchControl,
+    handle: JoinHandle<Result<Vec<Evaluation>, String>>,
+}
+
+impl BackgroundAnalysis {
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    // Stop the analysis, the thread is left to finish on it's own
+    pub fn cancel(&self) {
+        self.control.stop();
+    }
+
+    pub fn join(self) -> Result<Vec<Evaluation>, String> {
+        self.handle
+            .join()
+            .unwrap_or_else(|_| Err("Analysis thread panicked".to_string()))
+    }
+}
+
 pub struct AlphaBetaIteration {
     depth: usize,
     alpha: i32,
@@ -71,38 +422,322 @@ pub struct MinimaxAction<'a> {
     patterns: Option<&'a PatternCount>,
 }
 
+// Node of the Monte Carlo tree, the children are indexes in the same tree
+struct MonteCarloNode {
+    movement: Option<Move>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    // Moves that are not a child yet, generated on the first visit
+    unexpanded: Option<BinaryHeap<SortedMove>>,
+    visits: u32,
+    // Sum of the playouts rewards for the player of the movement
+    reward: f64,
+    // The game is over after the movement, None is a draw
+    result: Option<Option<Player>>,
+}
+
+impl MonteCarloNode {
+    fn new(movement: Option<Move>, parent: Option<usize>) -> Self {
+        MonteCarloNode {
+            movement,
+            parent,
+            children: vec![],
+            unexpanded: None,
+            visits: 0,
+            reward: 0.,
+            result: None,
+        }
+    }
+
+    fn win_rate(&self) -> f64 {
+        if self.visits == 0 {
+            return 0.;
+        }
+        self.reward / self.visits as f64
+    }
+
+    fn is_win(&self) -> bool {
+        matches!((self.result, self.movement), (Some(Some(winner)), Some(movement)) if winner == movement.player)
+    }
+
+    // Upper confidence bound of the node, used to select the child to explore
+    fn uct(&self, parent_visits: u32) -> f64 {
+        self.win_rate()
+            + MONTE_CARLO_EXPLORATION
+                * ((parent_visits as f64).ln() / self.visits.max(1) as f64).sqrt()
+    }
+
+    // Children are added one by one, in the heuristic order, as the node is visited
+    fn can_expand(&self) -> bool {
+        self.unexpanded
+            .as_ref()
+            .is_some_and(|moves| !moves.is_empty())
+            && self.children.len() <= (self.visits as f64).sqrt() as usize
+    }
+}
+
+// When the opponent has a four or an open three, the only moves worth searching
+// -- are the ones that win, block the four, or block the three or answer it with a four
+fn retain_threat_answers(moves: &mut BinaryHeap<SortedMove>) {
+    let is_win =
+        |patterns: &PatternCount| patterns.five_in_row > 0 || patterns.total_captures >= 10;
+    if moves
+        .iter()
+        .any(|sorted_move| is_win(&sorted_move.pattern_count))
+    {
+        moves.retain(|sorted_move| is_win(&sorted_move.pattern_count));
+    } else if moves
+        .iter()
+        .any(|sorted_move| sorted_move.pattern_count.kill_four > 0)
+    {
+        moves.retain(|sorted_move| sorted_move.pattern_count.kill_four > 0);
+    } else if moves
+        .iter()
+        .any(|sorted_move| sorted_move.pattern_count.reduce_three > 0)
+    {
+        moves.retain(|sorted_move| {
+            let patterns = &sorted_move.pattern_count;
+            patterns.reduce_three > 0
+                || patterns.open_four > 0
+                || patterns.close_four > 0
+                || patterns.inc_captures > 0
+        });
+    }
+}
+
 #[derive(Default, Clone)]
-pub struct Computer;
+pub struct Computer {
+    // Statistics of the last play
+    pub statistics: SearchStatistics,
+    pub control: SearchControl,
+    pub options: SearchOptions,
+    // Last two moves that caused a cutoff for each ply
+    pub killers: Vec<[Option<Coordinates>; 2]>,
+    // Sum of the squared remaining depth of the cutoffs caused by a move, for each player
+    pub history: Vec<[[u32; BOARD_SIZE_USIZE]; BOARD_SIZE_USIZE]>,
+    // Nodes of the last negamax or minimax search, only recorded when it's set
+    pub tree: Option<SearchTree>,
+    // Chance to play another good move than the searched one
+    pub randomness: Option<Randomness>,
+}
 
 impl Computer {
     // Calculate the patterns created by a movement and return it's score
-    pub fn evaluate_action(&self, action: &MinimaxAction) -> i32 {
+    pub fn evaluate_action(&mut self, action: &MinimaxAction) -> i32 {
+        self.statistics.leaf_evaluations += 1;
         HEURISTIC.patterns_score(action.patterns.as_ref().unwrap())
     }
 
+    // Score of a finished game for the player of the movement, None if the game continues
+    // -- a win is better the closer it is to the root, and a loss the further it is
+    // -- a draw by the move limit is 0, the other draws are found when generating the moves
+    fn terminal_score(
+        &mut self,
+        rules: &RuleSet,
+        action: &MinimaxAction,
+        remaining_depth: usize,
+    ) -> Option<i32> {
+        let movement = action.movement?;
+        let ply = self.statistics.depth.saturating_sub(remaining_depth) as i32;
+        let score = if action.board.is_winning(rules, movement.player) {
+            WIN_SCORE - ply
+        } else if action.board.is_winning(rules, movement.player.opponent()) {
+            -(WIN_SCORE - ply)
+        } else if action.board.reached_move_limit(rules) {
+            0
+        } else {
+            return None;
+        };
+        self.statistics.leaf_evaluations += 1;
+        Some(score)
+    }
+
+    #[inline(always)]
+    fn is_root(&self, remaining_depth: usize) -> bool {
+        remaining_depth == self.statistics.depth
+    }
+
+    // A stopped search returns partial evaluations that can't be compared
+    // -- only the root keep the first one to always have a move to play
+    fn should_stop(&self, remaining_depth: usize, best_eval: &Evaluation) -> bool {
+        self.control.is_stopped()
+            && !(self.is_root(remaining_depth) && best_eval.movements.is_empty())
+    }
+
+    // Generate all legal moves of the player, sorted by their best pattern
+    // -- and then by the killer moves and history scores
+    fn sorted_moves(
+        &self,
+        rules: &RuleSet,
+        board: &mut Board,
+        player: Player,
+        remaining_depth: usize,
+    ) -> BinaryHeap<SortedMove> {
+        let ply = self.statistics.depth.saturating_sub(remaining_depth);
+        let intersections =
+            board.intersections_legal_moves_within(rules, player, self.options.radius);
+        let mut moves: BinaryHeap<SortedMove> = BinaryHeap::new();
+        moves.reserve(intersections.len());
+        for movement in intersections {
+            let captures = board.set_move(rules, &movement);
+            let pattern_count =
+                HEURISTIC.count_movement_patterns(rules, board, &movement, captures);
+            board.undo_move(rules, &movement);
+            moves.push(SortedMove {
+                order: self.move_order(&movement, ply),
+                movement,
+                best_pattern: pattern_count.best_pattern(),
+                pattern_count,
+            });
+        }
+        if self.options.threats_only {
+            retain_threat_answers(&mut moves);
+        }
+        if self.options.beam_width > 0 && moves.len() > self.options.beam_width {
+            let mut best_moves: BinaryHeap<SortedMove> = BinaryHeap::new();
+            best_moves.reserve(self.options.beam_width);
+            while best_moves.len() < self.options.beam_width {
+                best_moves.push(moves.pop().unwrap());
+            }
+            moves = best_moves;
+        }
+        moves
+    }
+
+    // Moves the search considers first at the root of the position, from the best one
+    pub fn root_candidates(
+        &self,
+        rules: &RuleSet,
+        board: &Board,
+        player: Player,
+    ) -> Vec<SortedMove> {
+        let mut board = board.clone();
+        let mut candidates = self
+            .sorted_moves(rules, &mut board, player, self.statistics.depth)
+            .into_sorted_vec();
+        candidates.reverse();
+        candidates
+    }
+
+    fn move_order(&self, movement: &Move, ply: usize) -> u32 {
+        let coordinates = &movement.coordinates;
+        if self.options.ordering.use_killers() {
+            if let Some(killers) = self.killers.get(ply) {
+                for (slot, killer) in killers.iter().enumerate() {
+                    if killer.as_ref() == Some(coordinates) {
+                        return KILLER_BONUS[slot];
+                    }
+                }
+            }
+        }
+        if self.options.ordering.use_history() {
+            if let Some(history) = self.history.get(movement.player as usize) {
+                return history[coordinates.y as usize][coordinates.x as usize];
+            }
+        }
+        0
+    }
+
+    // Remember a move that caused a beta cutoff
+    fn store_cutoff(&mut self, movement: &Move, remaining_depth: usize) {
+        self.statistics.cutoffs += 1;
+        if let Some(tree) = &mut self.tree {
+            tree.cutoff(movement);
+        }
+        let ply = self.statistics.depth.saturating_sub(remaining_depth);
+        if self.options.ordering.use_killers() {
+            if self.killers.len() <= ply {
+                self.killers.resize(ply + 1, [None, None]);
+            }
+            let killers = &mut self.killers[ply];
+            if killers[0] != Some(movement.coordinates) {
+                killers[1] = killers[0];
+                killers[0] = Some(movement.coordinates);
+            }
+        }
+        if self.options.ordering.use_history() {
+            if self.history.is_empty() {
+                self.history = vec![[[0; BOARD_SIZE_USIZE]; BOARD_SIZE_USIZE]; 2];
+            }
+            let score = &mut self.history[movement.player as usize]
+                [movement.coordinates.y as usize][movement.coordinates.x as usize];
+            *score = score.saturating_add((remaining_depth * remaining_depth) as u32);
+        }
+    }
+
+    // Count a visited node and the ply it was found in
+    fn visit_node(&mut self, remaining_depth: usize) {
+        self.statistics.nodes += 1;
+        let ply = self.statistics.depth.saturating_sub(remaining_depth);
+        if ply > self.statistics.max_depth {
+            self.statistics.max_depth = ply;
+        }
+    }
+
+    // Record the node in the search tree, it's exited with the result of the search
+    fn enter_tree_node(
+        &mut self,
+        action: &MinimaxAction,
+        iteration: &AlphaBetaIteration,
+    ) -> Option<usize> {
+        self.tree.as_mut()?.enter(
+            action.movement.copied(),
+            action.patterns.cloned(),
+            (iteration.alpha, iteration.beta),
+        )
+    }
+
+    fn exit_tree_node(&mut self, node: Option<usize>, result: &Result<Evaluation, String>) {
+        if let Some(tree) = &mut self.tree {
+            // The score of a node whose search was stopped can't be compared
+            let score = result
+                .as_ref()
+                .ok()
+                .filter(|_| !self.control.is_stopped())
+                .map(|evaluation| evaluation.score);
+            tree.exit(node, score);
+        }
+    }
+
     // * Negamax function
 
     fn negamax_alpha_beta(
-        &self,
+        &mut self,
         rules: &RuleSet,
         action: MinimaxAction,
         iteration: AlphaBetaIteration,
         player: Player,
         color: i32,
-        start_time: Instant,
     ) -> Result<Evaluation, String> {
+        let node = self.enter_tree_node(&action, &iteration);
+        let result = self.negamax_node(rules, action, iteration, player, color);
+        self.exit_tree_node(node, &result);
+        result
+    }
+
+    fn negamax_node(
+        &mut self,
+        rules: &RuleSet,
+        action: MinimaxAction,
+        iteration: AlphaBetaIteration,
+        player: Player,
+        color: i32,
+    ) -> Result<Evaluation, String> {
+        self.visit_node(iteration.depth);
         let mut alpha = iteration.alpha;
         let beta = iteration.beta;
 
-        // Check if it's a leaf and compute it's valuelet is_leaf = iteration.depth == 0
-        let is_leaf = iteration.depth == 0
-            || if let Some(movement) = action.movement {
-                action.board.is_winning(rules, movement.player)
-                    || action.board.is_winning(rules, movement.player.opponent())
-            } else {
-                false
-            };
-        if is_leaf {
+        // A finished game is scored with its distance from the root
+        if let Some(score) = self.terminal_score(rules, &action, iteration.depth) {
+            return Ok(Evaluation {
+                score: -score,
+                movements: vec![],
+                ..Default::default()
+            });
+        }
+
+        // Check if it's a leaf and compute it's value
+        if iteration.depth == 0 {
             if action.movement.is_none() {
                 return Err("Empty movement in negamax leaf".to_string());
             }
@@ -110,6 +745,7 @@ impl Computer {
             return Ok(Evaluation {
                 score: color * score,
                 movements: vec![],
+                ..Default::default()
             });
         }
 
@@ -117,36 +753,26 @@ impl Computer {
         let mut best_eval = Evaluation {
             score: i32::min_value() + 1,
             movements: vec![],
+            ..Default::default()
         };
 
         // Iterate each neighbor moves
-        let intersections = action.board.intersections_legal_moves(rules, player);
-        let mut moves: BinaryHeap<SortedMove> = BinaryHeap::new();
-        moves.reserve(intersections.len());
-        for movement in intersections {
-            let captures = action.board.set_move(rules, &movement);
-            let pattern_count =
-                HEURISTIC.count_movement_patterns(rules, action.board, &movement, captures);
-            action.board.undo_move(rules, &movement);
-            moves.push(SortedMove {
-                movement,
-                best_pattern: pattern_count.best_pattern(),
-                pattern_count,
-            });
-        }
+        let mut moves = self.sorted_moves(rules, action.board, player, iteration.depth);
 
-        // Check if there is no moves remaining
+        // Check if there is no moves remaining, it's a draw if there is no legal move at all
         if moves.is_empty() {
-            if action.movement.is_none() {
+            if action.movement.is_none() || action.board.draw_reason(rules, player).is_some() {
                 return Ok(Evaluation {
                     score: 0,
                     movements: vec![],
+                    ..Default::default()
                 });
             } else {
                 let score = self.evaluate_action(&action);
                 return Ok(Evaluation {
                     score: color * score,
                     movements: vec![],
+                    ..Default::default()
                 });
             }
         }
@@ -167,52 +793,251 @@ impl Computer {
                 },
                 player.opponent(),
                 -color,
-                start_time,
             )?;
             action.board.undo_move(rules, &sorted_movement.movement);
+            if self.should_stop(iteration.depth, &best_eval) {
+                break;
+            }
             let score = -eval.score;
             if score > best_eval.score {
                 alpha = score;
                 best_eval.score = score;
                 best_eval.movements = eval.movements;
                 best_eval.movements.insert(0, sorted_movement.movement);
+                if self.is_root(iteration.depth) {
+                    self.control.publish(&best_eval);
+                }
                 if alpha >= beta {
+                    self.store_cutoff(&sorted_movement.movement, iteration.depth);
                     return Ok(best_eval);
                 }
             }
-            if start_time.elapsed().as_millis() > 480 && iteration.depth == DEPTH {
+        }
+
+        Ok(best_eval)
+    }
+
+    // * Principal variation search
+
+    // Same as negamax, but only the first move is searched with the full window
+    // -- the others are searched with a null window and only searched again if they are better
+    fn principal_variation_search(
+        &mut self,
+        rules: &RuleSet,
+        action: MinimaxAction,
+        iteration: AlphaBetaIteration,
+        player: Player,
+        color: i32,
+    ) -> Result<Evaluation, String> {
+        self.visit_node(iteration.depth);
+        let mut alpha = iteration.alpha;
+        let beta = iteration.beta;
+
+        if let Some(score) = self.terminal_score(rules, &action, iteration.depth) {
+            return Ok(Evaluation {
+                score: -score,
+                movements: vec![],
+                ..Default::default()
+            });
+        }
+
+        // Check if it's a leaf and compute it's value
+        if iteration.depth == 0 {
+            if action.movement.is_none() {
+                return Err("Empty movement in principal variation leaf".to_string());
+            }
+            let score = self.evaluate_action(&action);
+            return Ok(Evaluation {
+                score: color * score,
+                movements: vec![],
+                ..Default::default()
+            });
+        }
+
+        let mut best_eval = Evaluation {
+            score: i32::min_value() + 1,
+            movements: vec![],
+            ..Default::default()
+        };
+
+        let mut moves = self.sorted_moves(rules, action.board, player, iteration.depth);
+        if moves.is_empty() {
+            let score =
+                if action.movement.is_none() || action.board.draw_reason(rules, player).is_some() {
+                    0
+                } else {
+                    color * self.evaluate_action(&action)
+                };
+            return Ok(Evaluation {
+                score,
+                movements: vec![],
+                ..Default::default()
+            });
+        }
+
+        let mut first_move = true;
+        while let Some(sorted_movement) = moves.pop() {
+            action.board.set_move(rules, &sorted_movement.movement);
+            let mut child_search = |computer: &mut Computer, alpha: i32, beta: i32| {
+                computer.principal_variation_search(
+                    rules,
+                    MinimaxAction {
+                        board: action.board,
+                        movement: Some(&sorted_movement.movement),
+                        patterns: Some(&sorted_movement.pattern_count),
+                    },
+                    AlphaBetaIteration {
+                        depth: iteration.depth - 1,
+                        alpha: -beta,
+                        beta: -alpha,
+                    },
+                    player.opponent(),
+                    -color,
+                )
+            };
+            let mut eval = if first_move {
+                child_search(self, alpha, beta)?
+            } else {
+                let eval = child_search(self, alpha, alpha + 1)?;
+                let score = -eval.score;
+                if score > alpha && score < beta && !self.control.is_stopped() {
+                    child_search(self, alpha, beta)?
+                } else {
+                    eval
+                }
+            };
+            action.board.undo_move(rules, &sorted_movement.movement);
+            if self.should_stop(iteration.depth, &best_eval) {
                 break;
             }
+            first_move = false;
+            let score = -eval.score;
+            if score > best_eval.score {
+                best_eval.score = score;
+                eval.movements.insert(0, sorted_movement.movement);
+                best_eval.movements = eval.movements;
+                if self.is_root(iteration.depth) {
+                    self.control.publish(&best_eval);
+                }
+                if score > alpha {
+                    alpha = score;
+                }
+                if alpha >= beta {
+                    self.store_cutoff(&sorted_movement.movement, iteration.depth);
+                    return Ok(best_eval);
+                }
+            }
         }
 
         Ok(best_eval)
     }
 
+    // Search each depth up to the requested one with the principal variation search
+    // -- every iteration after the first is searched in a window around the previous score
+    // -- and searched again with the full window if the score falls outside of it
+    fn iterative_deepening(
+        &mut self,
+        rules: &RuleSet,
+        board: &mut Board,
+        depth: usize,
+        player: Player,
+        start_time: Instant,
+    ) -> Result<Evaluation, String> {
+        let full_window = (i32::min_value() + 1, i32::max_value());
+        let mut best_eval: Option<Evaluation> = None;
+        for iteration_depth in 1..=depth {
+            self.statistics.depth = iteration_depth;
+            // There is no window around a forced win or loss, the score only depends on the distance
+            let window = match &best_eval {
+                Some(previous) if previous.mate_in().is_none() => (
+                    previous
+                        .score
+                        .saturating_sub(ASPIRATION_WINDOW)
+                        .max(full_window.0),
+                    previous.score.saturating_add(ASPIRATION_WINDOW),
+                ),
+                _ => full_window,
+            };
+            let mut search = |computer: &mut Computer, (alpha, beta): (i32, i32)| {
+                computer.principal_variation_search(
+                    rules,
+                    MinimaxAction {
+                        board: &mut *board,
+                        movement: None,
+                        patterns: None,
+                    },
+                    AlphaBetaIteration {
+                        depth: iteration_depth,
+                        alpha,
+                        beta,
+                    },
+                    player,
+                    1,
+                )
+            };
+            let mut eval = search(self, window)?;
+            if window != full_window
+                && (eval.score <= window.0 || eval.score >= window.1)
+                && !self.control.is_stopped()
+            {
+                eval = search(self, full_window)?;
+            }
+            // An interrupted iteration is only kept if there is nothing better
+            if self.control.is_stopped() && best_eval.is_some() {
+                self.statistics.depth = iteration_depth - 1;
+                break;
+            }
+            self.statistics
+                .iterations
+                .push((iteration_depth, start_time.elapsed()));
+            best_eval = Some(eval);
+            if self.control.is_stopped() {
+                break;
+            }
+        }
+        best_eval.ok_or_else(|| "No iteration in principal variation search".to_string())
+    }
+
     // * Minimax function
 
     fn minimax_alpha_beta(
-        &self,
+        &mut self,
         rules: &RuleSet,
         action: MinimaxAction,
         iteration: AlphaBetaIteration,
         player: Player,
         maximize: bool,
-        start_time: Instant,
     ) -> Result<Evaluation, String> {
+        let node = self.enter_tree_node(&action, &iteration);
+        let result = self.minimax_node(rules, action, iteration, player, maximize);
+        self.exit_tree_node(node, &result);
+        result
+    }
+
+    fn minimax_node(
+        &mut self,
+        rules: &RuleSet,
+        action: MinimaxAction,
+        iteration: AlphaBetaIteration,
+        player: Player,
+        maximize: bool,
+    ) -> Result<Evaluation, String> {
+        self.visit_node(iteration.depth);
         let mut alpha = iteration.alpha;
         let mut beta = iteration.beta;
 
+        // A finished game is scored with its distance from the root
+        // -- the current action is a movement for the *other* player
+        if let Some(score) = self.terminal_score(rules, &action, iteration.depth) {
+            return Ok(Evaluation {
+                score: if maximize { -score } else { score },
+                movements: vec![],
+                ..Default::default()
+            });
+        }
+
         // Check if it's a leaf and compute it's value
-        // The current action is a movement for the *other* player
-        // -- so we need to check if the *other* player is winning
-        let is_leaf = iteration.depth == 0
-            || if let Some(movement) = action.movement {
-                action.board.is_winning(rules, movement.player)
-                    || action.board.is_winning(rules, movement.player.opponent())
-            } else {
-                false
-            };
-        if is_leaf {
+        if iteration.depth == 0 {
             if action.movement.is_none() {
                 return Err("Empty movement in minimax leaf".to_string());
             }
@@ -220,37 +1045,27 @@ impl Computer {
             return Ok(Evaluation {
                 score,
                 movements: vec![],
+                ..Default::default()
             });
         }
 
         // Generate moves
-        let intersections = action.board.intersections_legal_moves(rules, player);
-        let mut moves: BinaryHeap<SortedMove> = BinaryHeap::new();
-        moves.reserve(intersections.len());
-        for movement in intersections {
-            let captures = action.board.set_move(rules, &movement);
-            let pattern_count =
-                HEURISTIC.count_movement_patterns(rules, action.board, &movement, captures);
-            action.board.undo_move(rules, &movement);
-            moves.push(SortedMove {
-                movement,
-                best_pattern: pattern_count.best_pattern(),
-                pattern_count,
-            });
-        }
+        let mut moves = self.sorted_moves(rules, action.board, player, iteration.depth);
 
-        // Check if there is no moves remaining
+        // Check if there is no moves remaining, it's a draw if there is no legal move at all
         if moves.is_empty() {
-            if action.movement.is_none() {
+            if action.movement.is_none() || action.board.draw_reason(rules, player).is_some() {
                 return Ok(Evaluation {
                     score: 0,
                     movements: vec![],
+                    ..Default::default()
                 });
             } else {
                 let score = self.evaluate_action(&action);
                 return Ok(Evaluation {
                     score,
                     movements: vec![],
+                    ..Default::default()
                 });
             }
         }
@@ -260,6 +1075,7 @@ impl Computer {
             let mut best_eval = Evaluation {
                 score: i32::min_value() + 1,
                 movements: vec![],
+                ..Default::default()
             };
             while let Some(sorted_movement) = moves.pop() {
                 action.board.set_move(rules, &sorted_movement.movement);
@@ -277,23 +1093,26 @@ impl Computer {
                     },
                     player.opponent(),
                     !maximize,
-                    start_time,
                 )?;
                 action.board.undo_move(rules, &sorted_movement.movement);
+                if self.should_stop(iteration.depth, &best_eval) {
+                    break;
+                }
                 if eval.score > best_eval.score {
                     best_eval.score = eval.score;
                     best_eval.movements = eval.movements;
                     best_eval.movements.insert(0, sorted_movement.movement);
+                    if self.is_root(iteration.depth) {
+                        self.control.publish(&best_eval);
+                    }
                 }
                 if eval.score > alpha {
                     alpha = eval.score;
                 }
                 if eval.score >= beta {
+                    self.store_cutoff(&sorted_movement.movement, iteration.depth);
                     return Ok(best_eval);
                 }
-                if start_time.elapsed().as_millis() > 480 && iteration.depth == DEPTH {
-                    break;
-                }
             }
             Ok(best_eval)
         }
@@ -302,6 +1121,7 @@ impl Computer {
             let mut best_eval = Evaluation {
                 score: i32::max_value(),
                 movements: vec![],
+                ..Default::default()
             };
             while let Some(sorted_movement) = moves.pop() {
                 action.board.set_move(rules, &sorted_movement.movement);
@@ -319,9 +1139,11 @@ impl Computer {
                     },
                     player.opponent(),
                     !maximize,
-                    start_time,
                 )?;
                 action.board.undo_move(rules, &sorted_movement.movement);
+                if self.should_stop(iteration.depth, &best_eval) {
+                    break;
+                }
                 if eval.score < best_eval.score {
                     best_eval.score = eval.score;
                     best_eval.movements = eval.movements;
@@ -331,11 +1153,9 @@ impl Computer {
                     beta = eval.score;
                 }
                 if eval.score <= alpha {
+                    self.store_cutoff(&sorted_movement.movement, iteration.depth);
                     return Ok(best_eval);
                 }
-                if start_time.elapsed().as_millis() > 480 && iteration.depth == DEPTH {
-                    break;
-                }
             }
             Ok(best_eval)
         }
@@ -344,23 +1164,26 @@ impl Computer {
     // * Greedy function
 
     fn greedy(
-        &self,
+        &mut self,
         rules: &RuleSet,
         action: MinimaxAction,
         depth: usize,
         player: Player,
     ) -> Result<Evaluation, String> {
+        self.visit_node(depth);
+        // A finished game is scored with its distance from the root
+        // -- the movements of the root player are on the odd plies
+        if let Some(score) = self.terminal_score(rules, &action, depth) {
+            let ply = self.statistics.depth.saturating_sub(depth);
+            return Ok(Evaluation {
+                score: if ply % 2 == 1 { score } else { -score },
+                movements: vec![],
+                ..Default::default()
+            });
+        }
+
         // Check if it's a leaf and compute it's value
-        // The current action is a movement for the *other* player
-        // -- so we need to check if the *other* player is winning
-        let is_leaf = depth == 0
-            || if let Some(movement) = action.movement {
-                action.board.is_winning(rules, movement.player)
-                    || action.board.is_winning(rules, movement.player.opponent())
-            } else {
-                false
-            };
-        if is_leaf {
+        if depth == 0 {
             if action.movement.is_none() {
                 return Err("Empty movement in minimax leaf".to_string());
             }
@@ -368,37 +1191,27 @@ impl Computer {
             return Ok(Evaluation {
                 score,
                 movements: vec![],
+                ..Default::default()
             });
         }
 
         // Generate moves
-        let intersections = action.board.intersections_legal_moves(rules, player);
-        let mut moves: BinaryHeap<SortedMove> = BinaryHeap::new();
-        moves.reserve(intersections.len());
-        for movement in intersections {
-            let captures = action.board.set_move(rules, &movement);
-            let pattern_count =
-                HEURISTIC.count_movement_patterns(rules, action.board, &movement, captures);
-            action.board.undo_move(rules, &movement);
-            moves.push(SortedMove {
-                movement,
-                best_pattern: pattern_count.best_pattern(),
-                pattern_count,
-            });
-        }
+        let mut moves = self.sorted_moves(rules, action.board, player, depth);
 
-        // Check if there is no moves remaining
+        // Check if there is no moves remaining, it's a draw if there is no legal move at all
         if moves.is_empty() {
-            if action.movement.is_none() {
+            if action.movement.is_none() || action.board.draw_reason(rules, player).is_some() {
                 return Ok(Evaluation {
                     score: 0,
                     movements: vec![],
+                    ..Default::default()
                 });
             } else {
                 let score = self.evaluate_action(&action);
                 return Ok(Evaluation {
                     score,
                     movements: vec![],
+                    ..Default::default()
                 });
             }
         }
@@ -420,20 +1233,214 @@ impl Computer {
         let mut merged_eval = Evaluation {
             score: eval.score,
             movements: eval.movements,
+            ..Default::default()
         };
         merged_eval.movements.insert(0, first_movement.movement);
         Ok(merged_eval)
     }
 
+    // * Monte Carlo tree search
+
+    // Count a node of the Monte Carlo search at the given ply
+    fn visit_ply(&mut self, ply: usize) {
+        self.statistics.nodes += 1;
+        if ply > self.statistics.max_depth {
+            self.statistics.max_depth = ply;
+        }
+    }
+
+    // Winner of the game after the movement, if there is one
+    fn movement_result(rules: &RuleSet, board: &Board, movement: &Move) -> Option<Option<Player>> {
+        if board.is_winning(rules, movement.player) {
+            Some(Some(movement.player))
+        } else if board.is_winning(rules, movement.player.opponent()) {
+            Some(Some(movement.player.opponent()))
+        } else if board.reached_move_limit(rules) {
+            Some(None)
+        } else {
+            None
+        }
+    }
+
+    // Play random moves selected from the best moves of the heuristic until the game is over
+    // -- the board is restored before returning the winner
+    fn rollout(
+        &mut self,
+        rules: &RuleSet,
+        board: &mut Board,
+        mut player: Player,
+        ply: usize,
+    ) -> Option<Player> {
+        let mut rng = rand::thread_rng();
+        let mut played: Vec<Move> = vec![];
+        let mut winner = None;
+        for rollout_ply in 0..MONTE_CARLO_ROLLOUT_DEPTH {
+            let mut moves = self.sorted_moves(rules, board, player, self.statistics.depth);
+            if moves.is_empty() {
+                break;
+            }
+            let candidates = moves.len().min(MONTE_CARLO_ROLLOUT_CANDIDATES);
+            // A winning move is always played
+            let skip = if moves.peek().unwrap().best_pattern >= 200 {
+                0
+            } else {
+                rng.gen_range(0..candidates)
+            };
+            for _ in 0..skip {
+                moves.pop();
+            }
+            let movement = moves.pop().unwrap().movement;
+            board.set_move(rules, &movement);
+            played.push(movement);
+            self.visit_ply(ply + rollout_ply + 1);
+            if let Some(result) = Computer::movement_result(rules, board, &movement) {
+                winner = result;
+                break;
+            }
+            player = player.opponent();
+        }
+        for movement in played.iter().rev() {
+            board.undo_move(rules, movement);
+        }
+        winner
+    }
+
+    // Line of the most visited children, starting from the root
+    fn monte_carlo_evaluation(tree: &[MonteCarloNode]) -> Evaluation {
+        let mut evaluation = Evaluation::default();
+        let mut node = &tree[0];
+        while let Some(child) = node
+            .children
+            .iter()
+            .map(|child| &tree[*child])
+            .max_by_key(|child| child.visits)
+        {
+            if evaluation.movements.is_empty() {
+                evaluation.score = (child.win_rate() * 1000.) as i32;
+            }
+            evaluation.movements.push(child.movement.unwrap());
+            evaluation.visits.push(child.visits);
+            node = child;
+        }
+        evaluation
+    }
+
+    // UCT search, the children of a node are added in the order of the heuristic
+    // -- and the playouts select their moves from the heuristic best moves
+    // -- the score of the evaluation is the win rate of the first move, per thousand
+    fn monte_carlo(
+        &mut self,
+        rules: &RuleSet,
+        board: &mut Board,
+        depth: usize,
+        player: Player,
+    ) -> Result<Evaluation, String> {
+        let mut tree = vec![MonteCarloNode::new(None, None)];
+        self.visit_ply(0);
+        for playout in 0..(depth * MONTE_CARLO_PLAYOUTS) {
+            // Selection and expansion
+            let mut node = 0;
+            let mut path: Vec<Move> = vec![];
+            let mut to_play = player;
+            while tree[node].result.is_none() {
+                if tree[node].unexpanded.is_none() {
+                    let moves = self.sorted_moves(rules, board, to_play, self.statistics.depth);
+                    if moves.is_empty() {
+                        tree[node].result = Some(None);
+                        break;
+                    }
+                    tree[node].unexpanded = Some(moves);
+                }
+                if tree[node].can_expand() {
+                    let movement = tree[node].unexpanded.as_mut().unwrap().pop().unwrap();
+                    let movement = movement.movement;
+                    board.set_move(rules, &movement);
+                    path.push(movement);
+                    let mut child = MonteCarloNode::new(Some(movement), Some(node));
+                    child.result = Computer::movement_result(rules, board, &movement);
+                    tree.push(child);
+                    let child = tree.len() - 1;
+                    tree[node].children.push(child);
+                    self.visit_ply(path.len());
+                    node = child;
+                    to_play = to_play.opponent();
+                    break;
+                }
+                // A winning move is always selected, there is nothing else to explore
+                let parent_visits = tree[node].visits;
+                let children = &tree[node].children;
+                node = match children.iter().find(|child| tree[**child].is_win()) {
+                    Some(child) => *child,
+                    None => *children
+                        .iter()
+                        .max_by(|a, b| {
+                            tree[**a]
+                                .uct(parent_visits)
+                                .total_cmp(&tree[**b].uct(parent_visits))
+                        })
+                        .unwrap(),
+                };
+                let movement = tree[node].movement.unwrap();
+                board.set_move(rules, &movement);
+                path.push(movement);
+                to_play = to_play.opponent();
+            }
+
+            // Simulation
+            self.statistics.leaf_evaluations += 1;
+            let winner = match tree[node].result {
+                Some(result) => result,
+                None => self.rollout(rules, board, to_play, path.len()),
+            };
+
+            // Backpropagation
+            let mut current = Some(node);
+            while let Some(index) = current {
+                let current_node = &mut tree[index];
+                current_node.visits += 1;
+                current_node.reward += match (winner, current_node.movement) {
+                    (None, _) | (_, None) => 0.5,
+                    (Some(winner), Some(movement)) if winner == movement.player => 1.,
+                    _ => 0.,
+                };
+                current = current_node.parent;
+            }
+            for movement in path.iter().rev() {
+                board.undo_move(rules, movement);
+            }
+
+            if self.control.is_stopped() || tree[0].result.is_some() {
+                break;
+            }
+            if playout % MONTE_CARLO_PLAYOUTS == 0 {
+                self.control
+                    .publish(&Computer::monte_carlo_evaluation(&tree));
+            }
+        }
+        let evaluation = Computer::monte_carlo_evaluation(&tree);
+        self.control.publish(&evaluation);
+        Ok(evaluation)
+    }
+
     pub fn play(
-        &self,
+        &mut self,
         algorithm: Algorithm,
         rules: &RuleSet,
         board: &mut Board,
         depth: usize,
         player: Player,
     ) -> Result<Evaluation, String> {
-        Ok(match algorithm {
+        self.statistics = SearchStatistics {
+            depth,
+            ..Default::default()
+        };
+        self.killers.clear();
+        self.history.clear();
+        if let Some(tree) = &mut self.tree {
+            tree.clear();
+        }
+        let start_time = Instant::now();
+        let mut evaluation = match algorithm {
             // Use the negamax algorithm with alpha beta prunning to get the next best move
             Algorithm::Negamax => self.negamax_alpha_beta(
                 rules,
@@ -449,7 +1456,6 @@ impl Computer {
                 },
                 player,
                 1,
-                Instant::now(),
             )?,
             // Use the minimax algorithm with alpha beta prunning to get the next best move
             Algorithm::Minimax => self.minimax_alpha_beta(
@@ -466,8 +1472,13 @@ impl Computer {
                 },
                 player,
                 true,
-                Instant::now(),
             )?,
+            // Use the principal variation search with iterative deepening and aspiration windows
+            Algorithm::PrincipalVariation => {
+                self.iterative_deepening(rules, board, depth, player, start_time)?
+            }
+            // Use the Monte Carlo tree search, with a number of playouts that depends on the depth
+            Algorithm::MonteCarlo => self.monte_carlo(rules, board, depth, player)?,
             // Select only the next move from the heuristic
             Algorithm::Greedy => self.greedy(
                 rules,
@@ -479,6 +1490,231 @@ impl Computer {
                 depth,
                 player,
             )?,
-        })
+        };
+        let duration = start_time.elapsed();
+        // Iterative deepening already saved the duration of each iteration
+        if self.statistics.iterations.is_empty() {
+            self.statistics.iterations.push((depth, duration));
+        }
+        self.statistics.duration = duration;
+        evaluation.statistics = self.statistics.clone();
+        Ok(evaluation)
+    }
+
+    // Score of the movement for its player with a search of the reply of the opponent
+    // -- the evaluation starts with the movement and keeps the statistics of the reply
+    pub fn evaluate_move(
+        &mut self,
+        algorithm: Algorithm,
+        rules: &RuleSet,
+        board: &mut Board,
+        depth: usize,
+        movement: &Move,
+    ) -> Result<Evaluation, String> {
+        board.set_move(rules, movement);
+        let result = if board.is_winning(rules, movement.player) {
+            Ok(Evaluation {
+                score: WIN_SCORE - 1,
+                ..Default::default()
+            })
+        } else if board.is_winning(rules, movement.player.opponent()) {
+            Ok(Evaluation {
+                score: -(WIN_SCORE - 1),
+                ..Default::default()
+            })
+        } else {
+            self.play(
+                algorithm,
+                rules,
+                board,
+                depth.saturating_sub(1).max(1),
+                movement.player.opponent(),
+            )
+            .map(|mut reply| {
+                // The forced wins and losses of the reply are one ply further from the movement
+                reply.score = -reply.score;
+                if reply.score.abs() > MATE_SCORE {
+                    reply.score -= reply.score.signum();
+                }
+                reply
+            })
+        };
+        board.undo_move(rules, movement);
+        let mut evaluation = result?;
+        evaluation.movements.insert(0, *movement);
+        if !evaluation.visits.is_empty() {
+            evaluation.visits.insert(0, 0);
+        }
+        Ok(evaluation)
+    }
+
+    // Score every legal move of the player with a search of the position after it (multi-PV)
+    // -- the evaluations are sorted from the best one
+    pub fn analyse(
+        &mut self,
+        algorithm: Algorithm,
+        rules: &RuleSet,
+        board: &mut Board,
+        depth: usize,
+        player: Player,
+    ) -> Result<Vec<Evaluation>, String> {
+        let start_time = Instant::now();
+        let mut statistics = SearchStatistics {
+            depth,
+            ..Default::default()
+        };
+        let mut evaluations = vec![];
+        for movement in board.intersections_legal_moves(rules, player) {
+            if self.control.is_stopped() {
+                break;
+            }
+            let evaluation = self.evaluate_move(algorithm, rules, board, depth, &movement)?;
+            statistics.nodes += evaluation.statistics.nodes + 1;
+            statistics.leaf_evaluations += evaluation.statistics.leaf_evaluations;
+            statistics.cutoffs += evaluation.statistics.cutoffs;
+            statistics.max_depth = statistics
+                .max_depth
+                .max(evaluation.statistics.max_depth + 1);
+            evaluations.push(evaluation);
+        }
+        evaluations.sort_by_key(|evaluation| Reverse(evaluation.score));
+        statistics.duration = start_time.elapsed();
+        statistics.iterations.push((depth, statistics.duration));
+        for evaluation in evaluations.iter_mut() {
+            evaluation.statistics = statistics.clone();
+        }
+        self.statistics = statistics;
+        Ok(evaluations)
+    }
+
+    // Replace the searched move by one of the best candidates, chosen with a chance of the randomness
+    // -- the candidates are weighted by the score of a short search, and are never a forced loss
+    // -- a forced win or loss found by the search is always kept
+    // -- the short searches count in the time of the search, once it's stopped the searched move is kept
+    pub fn randomize(
+        &mut self,
+        rules: &RuleSet,
+        board: &mut Board,
+        player: Player,
+        evaluation: Evaluation,
+        rng: &mut impl Rng,
+    ) -> Result<Evaluation, String> {
+        let Some(randomness) = self.randomness else {
+            return Ok(evaluation);
+        };
+        if self.control.is_stopped()
+            || evaluation.mate_in().is_some()
+            || evaluation.movements.is_empty()
+            || !rng.gen_bool(randomness.chance.clamp(0., 1.))
+        {
+            return Ok(evaluation);
+        }
+        // The statistics and the tree stay the ones of the search
+        let statistics = self.statistics.clone();
+        let tree = self.tree.take();
+        let mut choices = vec![];
+        let mut result = Ok(());
+        for candidate in self
+            .root_candidates(rules, board, player)
+            .into_iter()
+            .take(randomness.candidates)
+        {
+            if self.control.is_stopped() {
+                break;
+            }
+            match self.evaluate_move(
+                Algorithm::Negamax,
+                rules,
+                board,
+                RANDOMNESS_DEPTH,
+                &candidate.movement,
+            ) {
+                Ok(choice) if choice.mate_in().is_some_and(|moves| moves < 0) => (),
+                Ok(choice) => choices.push(choice),
+                Err(error) => {
+                    result = Err(error);
+                    break;
+                }
+            }
+        }
+        self.statistics = statistics.clone();
+        self.tree = tree;
+        result?;
+        // The scores of the interrupted searches can't be compared
+        if self.control.is_stopped() {
+            return Ok(evaluation);
+        }
+        let Some(best) = choices.iter().map(|choice| choice.score).max() else {
+            return Ok(evaluation);
+        };
+        let weights: Vec<f64> = choices
+            .iter()
+            .map(|choice| {
+                ((choice.score as f64 - best as f64) / randomness.temperature.max(1.)).exp()
+            })
+            .collect();
+        let mut target = rng.gen::<f64>() * weights.iter().sum::<f64>();
+        let mut chosen = choices.len() - 1;
+        for (index, weight) in weights.iter().enumerate() {
+            if target < *weight {
+                chosen = index;
+                break;
+            }
+            target -= weight;
+        }
+        let mut choice = choices.swap_remove(chosen);
+        if choice.movements.first() == evaluation.movements.first() {
+            return Ok(evaluation);
+        }
+        choice.statistics = statistics;
+        Ok(choice)
+    }
+
+    // Same as analyse but in another thread
+    pub fn analyse_in_background(
+        mut self,
+        algorithm: Algorithm,
+        rules: RuleSet,
+        mut board: Board,
+        depth: usize,
+        player: Player,
+    ) -> BackgroundAnalysis {
+        let control = SearchControl::default();
+        self.control = control.clone();
+        let handle =
+            thread::spawn(move || self.analyse(algorithm, &rules, &mut board, depth, player));
+        BackgroundAnalysis { control, handle }
+    }
+
+    // Same as play but in another thread, the Computer is given back when joining
+    pub fn play_in_background(
+        mut self,
+        algorithm: Algorithm,
+        rules: RuleSet,
+        mut board: Board,
+        depth: usize,
+        player: Player,
+    ) -> BackgroundPlay {
+        let control = SearchControl::default();
+        self.control = control.clone();
+        let handle = thread::spawn(move || {
+            let play_result = self
+                .play(algorithm, &rules, &mut board, depth, player)
+                .and_then(|evaluation| {
+                    self.randomize(
+                        &rules,
+                        &mut board,
+                        player,
+                        evaluation,
+                        &mut rand::thread_rng(),
+                    )
+                });
+            (self, play_result)
+        });
+        BackgroundPlay {
+            control,
+            started: Instant::now(),
+            handle,
+        }
     }
 }
@@ -19,3 +19,23 @@ pub const OPPOSITE_DIRECTIONS: [((i16, i16), (i16, i16)); 4] = [
     ((0, -1), (0, 1)),
 ];
 pub const DEPTH: usize = 4;
+// Depth of the search of each legal move in the analysis mode
+pub const ANALYSIS_DEPTH: usize = 3;
+// Depth of the search of each move of a finished game in the review
+pub const REVIEW_DEPTH: usize = 3;
+// Opening
+// First and last line of the central square where the opening stones are placed
+pub const OPENING_AREA: (i16, i16) = (7, 11);
+
+// Scores
+// A win on the board, the search removes the number of plies needed to reach it
+pub const WIN_SCORE: i32 = i32::MAX - 1;
+// Any score above is a forced win, and any score below the opposite a forced loss
+pub const MATE_SCORE: i32 = WIN_SCORE - 1000;
+// The computer accepts a draw offer when the score of its last move is not above
+pub const DRAW_ACCEPT_SCORE: i32 = 0;
+// The Monte Carlo search scores with its win rate per thousand instead, and can't prove a loss
+// -- it takes a draw when it doesn't win more than half of its playouts
+pub const MONTE_CARLO_DRAW_ACCEPT_RATE: i32 = 500;
+// -- and it resigns when it almost never wins one
+pub const MONTE_CARLO_RESIGN_RATE: i32 = 20;
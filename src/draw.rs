@@ -1,15 +1,19 @@
 use crate::{
-    constants::BOARD_SIZE,
-    game::{Game, GameMode, Opening, Winner},
+    board::{IllegalMove, Move},
+    constants::{BOARD_SIZE, OPENING_AREA},
+    game::{Game, GameMode, Opening, Winner, SAVE_FILE},
     macros::coord,
+    network::OpeningChoice,
     player::Player,
+    review::MoveQuality,
     rock::Rock,
 };
 use macroquad::{
     color_u8, hash,
     prelude::{
-        draw_circle, draw_circle_lines, draw_line, draw_rectangle_lines, draw_text, measure_text,
-        mouse_position, vec2, Color, Vec2, BLACK, BLUE, PURPLE, RED, WHITE,
+        draw_circle, draw_circle_lines, draw_line, draw_rectangle, draw_rectangle_lines, draw_text,
+        is_mouse_button_pressed, measure_text, mouse_position, vec2, Color, MouseButton, Vec2,
+        BLACK, BLUE, GOLD, GRAY, ORANGE, PURPLE, RED, WHITE,
     },
     ui::{root_ui, widgets},
 };
@@ -25,6 +29,10 @@ pub const TEXT_OFFSET: f32 = 20.;
 pub const FONT_SIZE: u16 = 20;
 pub const WIN_FONT_SIZE: u16 = 30;
 pub const POLICE_SIZE: f32 = 20.;
+// Number of the best analysed moves listed in the panel
+pub const ANALYSIS_LINES: usize = 5;
+// The review graph is drawn over the bottom of the goban
+pub const REVIEW_GRAPH_TOP: f32 = (GRID_WINDOW_SIZE - 200) as f32;
 
 // Colors
 pub const BLACK_SEMI: Color = color_u8!(0, 0, 0, 200);
@@ -74,6 +82,37 @@ pub fn draw_goban(game: &Game) {
         y += 6 * SQUARE_SIZE;
     }
 
+    // Draw the analysis heatmap, from green for the best move to red for the worst
+    if game.analysis_mode && game.analysis_position.as_ref() == Some(&game.rock_move) {
+        let worst = game.analysis.len().saturating_sub(1).max(1) as f32;
+        for (rank, evaluation) in game.analysis.iter().enumerate() {
+            let coordinates = evaluation.movements[0].coordinates;
+            let ratio = rank as f32 / worst;
+            let (x, y) = (
+                (coordinates.x * SQUARE_SIZE + BORDER_OFFSET) as f32,
+                (coordinates.y * SQUARE_SIZE + BORDER_OFFSET) as f32,
+            );
+            draw_rectangle(
+                x - (SQUARE_SIZE / 2) as f32,
+                y - (SQUARE_SIZE / 2) as f32,
+                SQUARE_SIZE as f32,
+                SQUARE_SIZE as f32,
+                Color::new(ratio, 1. - ratio, 0., 0.45),
+            );
+            if rank < ANALYSIS_LINES {
+                let rank_text = format!("{}", rank + 1);
+                let text_size = measure_text(&rank_text, None, FONT_SIZE, 1.);
+                draw_text(
+                    &rank_text,
+                    x - text_size.width / 2.,
+                    y - 8.,
+                    POLICE_SIZE,
+                    BLACK,
+                );
+            }
+        }
+    }
+
     // Draw movements
     let movements = game
         .board
@@ -133,6 +172,36 @@ pub fn draw_goban(game: &Game) {
         }
     }
 
+    // Highlight the last refused movement and the rocks that explain it
+    if let Some((coordinates, reason)) = &game.illegal_move {
+        if *reason == IllegalMove::OutOfOpeningArea {
+            let size = ((OPENING_AREA.1 - OPENING_AREA.0) * SQUARE_SIZE) as f32;
+            draw_rectangle_lines(
+                (OPENING_AREA.0 * SQUARE_SIZE + BORDER_OFFSET) as f32,
+                (OPENING_AREA.0 * SQUARE_SIZE + BORDER_OFFSET) as f32,
+                size,
+                size,
+                3.,
+                RED,
+            );
+        }
+        for rock in reason.rocks() {
+            draw_circle_lines(
+                (rock.x * SQUARE_SIZE + BORDER_OFFSET) as f32,
+                (rock.y * SQUARE_SIZE + BORDER_OFFSET) as f32,
+                20.,
+                3.,
+                RED,
+            );
+        }
+        let (x, y) = (
+            (coordinates.x * SQUARE_SIZE + BORDER_OFFSET) as f32,
+            (coordinates.y * SQUARE_SIZE + BORDER_OFFSET) as f32,
+        );
+        draw_line(x - 12., y - 12., x + 12., y + 12., 4., RED);
+        draw_line(x - 12., y + 12., x + 12., y - 12., 4., RED);
+    }
+
     // Draw computer expected movements
     if game.show_computer_generated_moves
         || (game.mode != GameMode::AvA && game.generate_recommended_move)
@@ -143,53 +212,68 @@ pub fn draw_goban(game: &Game) {
         } else {
             1
         };
-        let mut black = Color::new(0.0, 0.0, 0.0, 0.7);
-        let mut white = Color::new(1.0, 1.0, 1.0, 0.7);
-        // let mut highlight = Color::new(0.78, 0.48, 1.00, 0.7); // PURPLE
-        for (next, movement) in game.computer_expected_moves.iter().skip(skip).enumerate() {
-            if board.get(movement.coordinates.x, movement.coordinates.y) == Rock::None {
-                let (x, y) = (movement.coordinates.x, movement.coordinates.y);
-                let draw_x = (x * SQUARE_SIZE + BORDER_OFFSET) as f32;
-                let draw_y = (y * SQUARE_SIZE + BORDER_OFFSET) as f32;
-                draw_circle(
-                    draw_x,
-                    draw_y,
+        draw_moves_line(
+            game,
+            &game.computer_expected_moves[skip.min(game.computer_expected_moves.len())..],
+            game.rock_move.len() + move_number_inc,
+            game.generate_recommended_move,
+        );
+    }
+}
+
+// Draw a line of upcoming moves with their move number below them, fading after each move
+fn draw_moves_line(
+    game: &Game,
+    movements: &[Move],
+    first_move_number: usize,
+    highlight_first: bool,
+) {
+    let board = &game.board;
+    let mut black = Color::new(0.0, 0.0, 0.0, 0.7);
+    let mut white = Color::new(1.0, 1.0, 1.0, 0.7);
+    // let mut highlight = Color::new(0.78, 0.48, 1.00, 0.7); // PURPLE
+    for (next, movement) in movements.iter().enumerate() {
+        if board.get(movement.coordinates.x, movement.coordinates.y) == Rock::None {
+            let (x, y) = (movement.coordinates.x, movement.coordinates.y);
+            let draw_x = (x * SQUARE_SIZE + BORDER_OFFSET) as f32;
+            let draw_y = (y * SQUARE_SIZE + BORDER_OFFSET) as f32;
+            draw_circle(
+                draw_x,
+                draw_y,
+                20.,
+                if movement.player == Player::Black {
+                    black
+                } else {
+                    white
+                },
+            );
+            // Add highlight for recommended move
+            if highlight_first && next == 0 {
+                draw_circle_lines(
+                    (x * SQUARE_SIZE + BORDER_OFFSET) as f32,
+                    (y * SQUARE_SIZE + BORDER_OFFSET) as f32,
                     20.,
-                    if movement.player == Player::Black {
-                        black
-                    } else {
-                        white
-                    },
-                );
-                // Add highlight for recommended move
-                if game.generate_recommended_move && next == 0 {
-                    draw_circle_lines(
-                        (x * SQUARE_SIZE + BORDER_OFFSET) as f32,
-                        (y * SQUARE_SIZE + BORDER_OFFSET) as f32,
-                        20.,
-                        2.,
-                        PURPLE,
-                    );
-                }
-                let next_text =
-                    format!("{}", game.rock_move.len() + next + move_number_inc).to_string();
-                let text_size = measure_text(&next_text, None, FONT_SIZE, 1.);
-                draw_text(
-                    &next_text,
-                    draw_x - (text_size.width / 2.),
-                    draw_y + text_size.height + 6.,
-                    POLICE_SIZE,
-                    if movement.player == Player::Black {
-                        white
-                    } else {
-                        black
-                    },
+                    2.,
+                    PURPLE,
                 );
-                // draw_circle_lines(draw_x, draw_y, 20., 1., highlight);
-                black.a -= 0.15;
-                white.a -= 0.15;
-                // highlight.a -= 0.1;
             }
+            let next_text = format!("{}", first_move_number + next).to_string();
+            let text_size = measure_text(&next_text, None, FONT_SIZE, 1.);
+            draw_text(
+                &next_text,
+                draw_x - (text_size.width / 2.),
+                draw_y + text_size.height + 6.,
+                POLICE_SIZE,
+                if movement.player == Player::Black {
+                    white
+                } else {
+                    black
+                },
+            );
+            // draw_circle_lines(draw_x, draw_y, 20., 1., highlight);
+            black.a -= 0.15;
+            white.a -= 0.15;
+            // highlight.a -= 0.1;
         }
     }
 }
@@ -220,9 +304,63 @@ pub fn options_selector(game: &mut Game) {
         ui.combo_box(
             hash!(),
             "Algorithm",
-            &["Negamax", "Minimax", "Greedy"],
+            &[
+                "Negamax",
+                "Minimax",
+                "Greedy",
+                "Principal variation",
+                "Monte Carlo",
+            ],
             &mut game.algorithm_index,
         );
+        ui.combo_box(
+            hash!(),
+            "Move ordering",
+            &["Patterns", "Killers", "History", "Killers and history"],
+            &mut game.ordering_index,
+        );
+        ui.combo_box(
+            hash!(),
+            "Candidates radius",
+            &["1", "2", "3"],
+            &mut game.radius_index,
+        );
+        ui.combo_box(
+            hash!(),
+            "Beam width",
+            &["All", "5", "10", "20"],
+            &mut game.beam_width_index,
+        );
+        ui.checkbox(
+            hash!(),
+            "Only search answers to threats",
+            &mut game.threats_only,
+        );
+        ui.combo_box(
+            hash!(),
+            "Move limit",
+            &["None", "100", "200", "300"],
+            &mut game.move_limit_index,
+        );
+        ui.combo_box(
+            hash!(),
+            "Time control",
+            &[
+                "None",
+                "5 min",
+                "10 min",
+                "3 min +2s",
+                "5 min +3s",
+                "5 min +5x30s",
+            ],
+            &mut game.time_control_index,
+        );
+        ui.combo_box(
+            hash!(),
+            "Replay speed",
+            &["0.25s", "0.5s", "1s", "2s"],
+            &mut game.replay_speed_index,
+        );
         ui.checkbox(hash!(), "Enable Capture", &mut game.rules.capture);
         ui.checkbox(
             hash!(),
@@ -239,11 +377,36 @@ pub fn options_selector(game: &mut Game) {
             "Generate recommended moves",
             &mut game.generate_recommended_move,
         );
+        ui.checkbox(
+            hash!(),
+            "Analysis mode (score every move)",
+            &mut game.analysis_mode,
+        );
         ui.checkbox(
             hash!(),
             "Display computer generated moves",
             &mut game.show_computer_generated_moves,
         );
+        ui.checkbox(hash!(), "Think on the opponent time", &mut game.ponder);
+        ui.checkbox(
+            hash!(),
+            "Computer resigns lost games",
+            &mut game.computer_resigns,
+        );
+        ui.checkbox(
+            hash!(),
+            &format!("Log the computer searches to {}", game.engine_log_path),
+            &mut game.engine_log,
+        );
+        ui.checkbox(
+            hash!(),
+            "Record the negamax and minimax search trees",
+            &mut game.record_search_tree,
+        );
+        ui.separator();
+        if ui.button(None, "Restore defaults") {
+            game.restore_default_settings();
+        }
     });
     let back_button = widgets::Button::new("Back")
         .size(Vec2::new(BUTTTON_LENGTH - 30., BUTTTON_HEIGTH - 30.))
@@ -254,6 +417,7 @@ pub fn options_selector(game: &mut Game) {
         .ui(ui);
     if back_button {
         game.in_options = false;
+        game.save_settings();
     }
 }
 
@@ -311,7 +475,105 @@ pub fn game_selector(game: &mut Game) -> bool {
         ))
         .ui(&mut root_ui());
 
+    // Navigate again in the moves of the previous game
+    if game.replay_length() > 0 {
+        let replay_button = widgets::Button::new("Replay last game")
+            .size(Vec2::new(BUTTTON_LENGTH, BUTTTON_HEIGTH - 30.))
+            .position(Vec2::new(
+                ((GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE) / 2) as f32 - BUTTTON_LENGTH / 2.,
+                (GRID_WINDOW_SIZE / 2) as f32 + BUTTTON_HEIGTH / 2. + 180.,
+            ))
+            .ui(&mut root_ui());
+        if replay_button {
+            match game.load_record(game.rules, game.variations.clone()) {
+                Ok(()) => return true,
+                Err(error) => println!("failed to replay the game: {}", error),
+            }
+        }
+    }
+
+    // Play against another instance on the local network
+    let mut host = false;
+    let mut join = false;
+    root_ui().window(
+        hash!(),
+        vec2(
+            40.,
+            (GRID_WINDOW_SIZE / 2) as f32 - BUTTTON_HEIGTH / 2. - 100.,
+        ),
+        vec2(BUTTTON_LENGTH * 1.25, 110.),
+        |ui| {
+            ui.label(None, "LAN game");
+            ui.input_text(hash!(), "Address", &mut game.network_address);
+            host = ui.button(None, "Host");
+            join = ui.button(None, "Join");
+        },
+    );
+    if host {
+        game.save_settings();
+        game.host_network_game();
+        return true;
+    } else if join {
+        game.join_network_game();
+        return true;
+    }
+
+    // Stream the next games to other instances, or follow the games of one of them
+    let mut broadcasting = game.broadcast.is_some();
+    let broadcast_status = match &game.broadcast {
+        Some(broadcast) => match (&broadcast.error, broadcast.local_address()) {
+            (Some(error), _) => error.clone(),
+            (None, Some(address)) => {
+                format!("{} spectators on {}", broadcast.spectators(), address)
+            }
+            (None, None) => "Writing the events".to_string(),
+        },
+        None => String::new(),
+    };
+    let mut watch = false;
+    root_ui().window(
+        hash!(),
+        vec2(
+            40.,
+            (GRID_WINDOW_SIZE / 2) as f32 - BUTTTON_HEIGTH / 2. + 40.,
+        ),
+        vec2(BUTTTON_LENGTH * 1.25, 130.),
+        |ui| {
+            ui.label(None, "Broadcast");
+            ui.input_text(hash!(), "Stream", &mut game.broadcast_address);
+            ui.checkbox(hash!(), "Broadcast the games", &mut broadcasting);
+            ui.label(None, &broadcast_status);
+            watch = ui.button(None, "Watch");
+        },
+    );
+    if broadcasting != game.broadcast.is_some() {
+        game.toggle_broadcast();
+    }
+    if watch {
+        game.watch();
+        return true;
+    }
+
+    // Resume the game saved or left in progress
+    if std::path::Path::new(SAVE_FILE).exists() {
+        let load_button = widgets::Button::new("Load saved game")
+            .size(Vec2::new(BUTTTON_LENGTH, BUTTTON_HEIGTH - 30.))
+            .position(Vec2::new(
+                ((GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE) / 2) as f32 - BUTTTON_LENGTH / 2.,
+                (GRID_WINDOW_SIZE / 2) as f32 + BUTTTON_HEIGTH / 2. + 230.,
+            ))
+            .ui(&mut root_ui());
+        if load_button {
+            if let Err(error) = game.load(SAVE_FILE) {
+                println!("failed to load the saved game: {}", error);
+            }
+            return game.playing;
+        }
+    }
+
     if pvp_button || pva_button || ava_button {
+        // Keep the opening chosen in the menu for the next launch
+        game.save_settings();
         game.start(if pvp_button {
             GameMode::PvP
         } else if pva_button {
@@ -325,6 +587,39 @@ pub fn game_selector(game: &mut Game) -> bool {
     }
 }
 
+// Connection of a network game before it starts
+pub fn network_lobby(game: &mut Game) {
+    let Some(network) = &game.network else {
+        return;
+    };
+    let status = if let Some(error) = &network.error {
+        error.clone()
+    } else if let Some(address) = network.local_address() {
+        format!("Waiting for an opponent on {}", address)
+    } else {
+        "Waiting for the rules of the host".to_string()
+    };
+    let text_size = measure_text(&status, None, FONT_SIZE, 1.);
+    draw_text(
+        &status,
+        ((GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE) / 2) as f32 - text_size.width / 2.,
+        (GRID_WINDOW_SIZE / 2) as f32,
+        POLICE_SIZE,
+        if network.error.is_some() { RED } else { BLACK },
+    );
+
+    let cancel_button = widgets::Button::new("Cancel")
+        .size(Vec2::new(BUTTTON_LENGTH - 30., BUTTTON_HEIGTH - 30.))
+        .position(Vec2::new(
+            (GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE / 2) as f32 - (BUTTTON_LENGTH - 30.) / 2.,
+            GRID_WINDOW_SIZE as f32 - 70.,
+        ))
+        .ui(&mut root_ui());
+    if cancel_button {
+        game.network = None;
+    }
+}
+
 pub fn color_selector(game: &mut Game) -> bool {
     let back_button = widgets::Button::new("Back")
         .size(Vec2::new(BUTTTON_LENGTH - 30., BUTTTON_HEIGTH - 30.))
@@ -356,6 +651,7 @@ pub fn color_selector(game: &mut Game) -> bool {
         ))
         .ui(&mut root_ui());
     if black_button {
+        game.save_settings();
         game.start_pva(Rock::Black);
         return true;
     }
@@ -368,6 +664,7 @@ pub fn color_selector(game: &mut Game) -> bool {
         ))
         .ui(&mut root_ui());
     if white_button {
+        game.save_settings();
         game.start_pva(Rock::White);
         return true;
     }
@@ -384,9 +681,20 @@ pub fn color_selector(game: &mut Game) -> bool {
         |ui| {
             ui.combo_box(
                 hash!(),
-                "Difficulty",
-                &["Easy", "Medium", "Hard (Slow)"],
-                &mut game.difficulty_index,
+                "Strength",
+                &[
+                    "1 (Beginner)",
+                    "2",
+                    "3",
+                    "4",
+                    "5",
+                    "6",
+                    "7",
+                    "8",
+                    "9",
+                    "10 (Slow)",
+                ],
+                &mut game.strength_index,
             );
         },
     );
@@ -439,6 +747,39 @@ pub fn display_panel_text(game: &mut Game) {
         BLACK,
     );
 
+    // Time left of each player, the player that is thinking is marked
+    if game.clock.is_enabled() {
+        for player in [Player::Black, Player::White] {
+            y_offset += TEXT_OFFSET;
+            let time = game.clock.player_time(player);
+            draw_text(
+                format!(
+                    "{}{}: {}",
+                    if game.winner == Winner::None && game.current_player == player {
+                        "> "
+                    } else {
+                        ""
+                    },
+                    if player == Player::Black {
+                        "Black"
+                    } else {
+                        "White"
+                    },
+                    game.clock.time_text(player)
+                )
+                .as_str(),
+                GRID_WINDOW_SIZE as f32 + TEXT_OFFSET,
+                y_offset,
+                POLICE_SIZE,
+                if time.flagged || (time.main.as_secs() < 10 && time.periods == 0) {
+                    RED
+                } else {
+                    BLACK
+                },
+            );
+        }
+    }
+
     if game.mode != GameMode::PvP && game.computer_average_play_time != 0. {
         y_offset += TEXT_OFFSET;
         let highest_play_time = game.computer_highest_play_time.as_millis();
@@ -492,6 +833,121 @@ pub fn display_panel_text(game: &mut Game) {
         );
     }
 
+    if game.mode != GameMode::PvP {
+        if let Some(statistics) = &game.computer_statistics {
+            let mut lines = vec![
+                format!("Nodes: {}", statistics.nodes),
+                format!("Leaves: {}", statistics.leaf_evaluations),
+                format!("Cutoffs: {}", statistics.cutoffs),
+                format!("Depth: {}/{}", statistics.max_depth, statistics.depth),
+                format!("EBF: {:.2}", statistics.effective_branching_factor()),
+                format!("Nodes/s: {:.0}", statistics.nodes_per_second()),
+                format!("Line: {} moves", game.computer_expected_moves.len()),
+            ];
+            match game.computer_mate_in {
+                Some(moves) if moves > 0 => lines.push(format!("Win in {}", moves)),
+                Some(moves) => lines.push(format!("Loss in {}", -moves)),
+                None => (),
+            }
+            for (depth, duration) in &statistics.iterations {
+                lines.push(format!("Depth {}: {}ms", depth, duration.as_millis()));
+            }
+            if !game.computer_visits.is_empty() {
+                let visits: Vec<String> = game
+                    .computer_visits
+                    .iter()
+                    .take(5)
+                    .map(|visits| visits.to_string())
+                    .collect();
+                lines.push(format!("Visits: {}", visits.join(" ")));
+            }
+            for line in lines {
+                y_offset += TEXT_OFFSET;
+                draw_text(
+                    line.as_str(),
+                    GRID_WINDOW_SIZE as f32 + TEXT_OFFSET,
+                    y_offset,
+                    POLICE_SIZE,
+                    BLACK,
+                );
+            }
+        }
+    }
+
+    if game.analysis_mode && game.analysing.is_some() {
+        y_offset += TEXT_OFFSET;
+        draw_text(
+            "Analysing the position...",
+            GRID_WINDOW_SIZE as f32 + TEXT_OFFSET,
+            y_offset,
+            POLICE_SIZE,
+            BLACK,
+        );
+    } else if game.analysis_mode && !game.analysis.is_empty() {
+        let mut lines = vec!["Analysis:".to_string()];
+        for (rank, evaluation) in game.analysis.iter().take(ANALYSIS_LINES).enumerate() {
+            let score = match evaluation.mate_in() {
+                Some(moves) if moves > 0 => format!("win in {}", moves),
+                Some(moves) => format!("loss in {}", -moves),
+                None => evaluation.score.to_string(),
+            };
+            lines.push(format!(
+                "{}. {} {}",
+                rank + 1,
+                evaluation.movements[0].coordinates,
+                score
+            ));
+            let continuation: Vec<String> = evaluation
+                .movements
+                .iter()
+                .skip(1)
+                .take(3)
+                .map(|movement| movement.coordinates.to_string())
+                .collect();
+            if !continuation.is_empty() {
+                lines.push(format!("    {}", continuation.join(" ")));
+            }
+        }
+        for line in lines {
+            y_offset += TEXT_OFFSET;
+            draw_text(
+                line.as_str(),
+                GRID_WINDOW_SIZE as f32 + TEXT_OFFSET,
+                y_offset,
+                POLICE_SIZE,
+                BLACK,
+            );
+        }
+    }
+
+    // Review of the last played move
+    let last_review = game.variations.current.and_then(|node| {
+        game.move_review(
+            game.rock_move.len() - 1,
+            &game.variations.nodes[node].movement,
+        )
+    });
+    if let Some(review) = last_review {
+        y_offset += TEXT_OFFSET;
+        draw_text(
+            format!("Review: {}", review.quality).as_str(),
+            GRID_WINDOW_SIZE as f32 + TEXT_OFFSET,
+            y_offset,
+            POLICE_SIZE,
+            BLACK,
+        );
+        if review.quality != MoveQuality::Best {
+            y_offset += TEXT_OFFSET;
+            draw_text(
+                format!("Best: {}", review.best_move.coordinates).as_str(),
+                GRID_WINDOW_SIZE as f32 + TEXT_OFFSET,
+                y_offset,
+                POLICE_SIZE,
+                BLACK,
+            );
+        }
+    }
+
     if game.rules.capture {
         y_offset += TEXT_OFFSET;
         draw_text(
@@ -529,9 +985,106 @@ pub fn display_panel_text(game: &mut Game) {
         BLACK,
     );
 
+    if let Some((coordinates, reason)) = &game.illegal_move {
+        let mut lines = vec![
+            format!("Illegal move {}", coordinates),
+            reason.description().to_string(),
+        ];
+        if let IllegalMove::DoubleFreeThree(threes) = reason {
+            for three in threes {
+                lines.push(format!("{} {} {}", three[0], three[1], three[2]));
+            }
+        }
+        for line in lines {
+            y_offset += TEXT_OFFSET;
+            draw_text(
+                line.as_str(),
+                GRID_WINDOW_SIZE as f32 + TEXT_OFFSET,
+                y_offset,
+                POLICE_SIZE,
+                RED,
+            );
+        }
+    }
+
+    let draw_offer_text = if let Some(player) = game.draw_offer {
+        Some((format!("{:?} offers a draw", player), BLUE))
+    } else if game.draw_declined {
+        Some(("Draw declined".to_string(), RED))
+    } else {
+        None
+    };
+    if let Some((line, color)) = draw_offer_text {
+        y_offset += TEXT_OFFSET;
+        draw_text(
+            line.as_str(),
+            GRID_WINDOW_SIZE as f32 + TEXT_OFFSET,
+            y_offset,
+            POLICE_SIZE,
+            color,
+        );
+    }
+
+    // Draw offers of a local game on the turn of a player, the network window has its own
+    if game.winner == Winner::None
+        && !game.replaying
+        && game.network.is_none()
+        && game.spectator.is_none()
+        && game.mode != GameMode::AvA
+        && (game.opening() == Opening::None || game.completed_opening)
+        && !(game.mode == GameMode::PvA && game.current_player == game.computer_play_as)
+    {
+        let x = (GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE / 2) as f32 - (BUTTTON_LENGTH - 30.) / 2.;
+        let y = GRID_WINDOW_SIZE as f32 - 70. - BUTTTON_HEIGTH * 0.75;
+        if game.draw_offer.is_some() {
+            let width = (BUTTTON_LENGTH - 30.) / 2. - 5.;
+            let accept_button = widgets::Button::new("Accept")
+                .size(Vec2::new(width, BUTTTON_HEIGTH - 30.))
+                .position(Vec2::new(x, y))
+                .ui(&mut root_ui());
+            let decline_button = widgets::Button::new("Decline")
+                .size(Vec2::new(width, BUTTTON_HEIGTH - 30.))
+                .position(Vec2::new(x + width + 10., y))
+                .ui(&mut root_ui());
+            if accept_button || decline_button {
+                game.answer_draw(accept_button);
+            }
+        } else {
+            let offer_button = widgets::Button::new("Offer draw")
+                .size(Vec2::new(BUTTTON_LENGTH - 30., BUTTTON_HEIGTH - 30.))
+                .position(Vec2::new(x, y))
+                .ui(&mut root_ui());
+            if offer_button {
+                game.offer_draw();
+            }
+        }
+    }
+
+    if game.winner == Winner::None
+        && !game.replaying
+        && !game.ask_player_choice
+        && game.network.is_none()
+        && game.spectator.is_none()
+    {
+        let save_button = widgets::Button::new("Save")
+            .size(Vec2::new(BUTTTON_LENGTH - 30., BUTTTON_HEIGTH - 30.))
+            .position(Vec2::new(
+                (GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE / 2) as f32 - (BUTTTON_LENGTH - 30.) / 2.,
+                GRID_WINDOW_SIZE as f32 - 70. - BUTTTON_HEIGTH * 2.,
+            ))
+            .ui(&mut root_ui());
+        if save_button {
+            if let Err(error) = game.save(SAVE_FILE) {
+                println!("failed to save the game: {}", error);
+            }
+        }
+    }
+
     let exit_button = widgets::Button::new(
         if game.mode == GameMode::AvA
+            || game.spectator.is_some()
             || game.winner != Winner::None
+            || game.replaying
             || (game.opening() != Opening::None && !game.completed_opening)
         {
             "Back"
@@ -547,54 +1100,352 @@ pub fn display_panel_text(game: &mut Game) {
     .ui(&mut root_ui());
 
     if exit_button {
+        game.cancel_computer_thinking();
         if game.winner == Winner::None
+            && !game.replaying
+            && game.spectator.is_none()
             && (game.opening() == Opening::None || game.completed_opening)
         {
-            game.winner = if game.current_player == Player::Black {
-                Winner::White
-            } else {
-                Winner::Black
-            };
+            game.resign();
         } else {
             game.playing = false;
+            game.network = None;
+            game.spectator = None;
         }
     }
 }
 
-pub fn display_winner(game: &mut Game) {
-    // Display undo and redo buttons
-    let x = (GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE / 2) as f32 - ((BUTTTON_LENGTH - 30.) / 2.);
-    let y = GRID_WINDOW_SIZE as f32 - 70. - (BUTTTON_HEIGTH * 1.5);
-    if !game.rock_move.is_empty() {
-        let undo = widgets::Button::new("Undo")
-            .size(Vec2::new((BUTTTON_LENGTH - 40.) / 2., BUTTTON_HEIGTH - 30.))
-            .position(Vec2::new(x, y))
+// State of the watched broadcast and the last evaluation of the computer
+pub fn draw_spectator(game: &Game) {
+    let Some(spectator) = &game.spectator else {
+        return;
+    };
+    let status = if let Some(error) = &spectator.error {
+        format!("Stopped: {}", error)
+    } else if game.rock_move.is_empty() && game.winner == Winner::None {
+        "Waiting for a game".to_string()
+    } else {
+        format!("Watching {}", game.broadcast_address)
+    };
+    let evaluation = match (game.computer_mate_in, spectator.score) {
+        (Some(mate_in), _) => format!("Mate in {}", mate_in),
+        (None, Some(score)) => format!("Score {}", score),
+        (None, None) => "No evaluation".to_string(),
+    };
+    widgets::Window::new(hash!(), vec2(10., 10.), vec2(260., 90.))
+        .label("Spectator")
+        .movable(true)
+        .ui(&mut root_ui(), |ui| {
+            ui.label(None, &status);
+            ui.label(None, &evaluation);
+        });
+}
+
+// Chat and actions of a network game, in a window that can be moved over the goban
+pub fn draw_network(game: &mut Game) {
+    let Some(network) = &game.network else {
+        return;
+    };
+    let status = if let Some(error) = &network.error {
+        format!("Disconnected: {}", error)
+    } else if game.opening() != Opening::None && !game.completed_opening {
+        "Opening".to_string()
+    } else {
+        format!(
+            "You play {}",
+            if network.local_player == Player::Black {
+                "Black"
+            } else {
+                "White"
+            }
+        )
+    };
+    let log: Vec<String> = network.log.iter().rev().take(8).rev().cloned().collect();
+    let undo_requested = network.undo_requested;
+    let draw_offered = network.draw_offered;
+    let can_request_undo = game.can_request_undo();
+    let can_offer_draw = network.is_connected() && game.winner == Winner::None;
+    let mut chat_input = network.chat_input.clone();
+    let (mut send, mut request_undo, mut offer_draw) = (false, false, false);
+    let (mut undo_answer, mut draw_answer) = (None, None);
+    widgets::Window::new(hash!(), vec2(10., 10.), vec2(260., 300.))
+        .label("Network")
+        .movable(true)
+        .ui(&mut root_ui(), |ui| {
+            ui.label(None, &status);
+            if undo_requested {
+                ui.label(None, "Take back the last move?");
+                if ui.button(None, "Accept undo") {
+                    undo_answer = Some(true);
+                }
+                ui.same_line(0.);
+                if ui.button(None, "Decline undo") {
+                    undo_answer = Some(false);
+                }
+            }
+            if draw_offered {
+                ui.label(None, "Draw offered");
+                if ui.button(None, "Accept draw") {
+                    draw_answer = Some(true);
+                }
+                ui.same_line(0.);
+                if ui.button(None, "Decline draw") {
+                    draw_answer = Some(false);
+                }
+            }
+            if can_request_undo {
+                request_undo = ui.button(None, "Request undo");
+                ui.same_line(0.);
+            }
+            if can_offer_draw {
+                offer_draw = ui.button(None, "Offer draw");
+            }
+            ui.separator();
+            for line in &log {
+                ui.label(None, line);
+            }
+            ui.separator();
+            ui.input_text(hash!(), "", &mut chat_input);
+            send = ui.button(None, "Send");
+        });
+    if let Some(network) = &mut game.network {
+        network.chat_input = chat_input;
+    }
+    if send {
+        game.send_chat();
+    }
+    if request_undo {
+        game.request_undo();
+    }
+    if offer_draw {
+        game.offer_draw();
+    }
+    if let Some(accept) = undo_answer {
+        game.answer_undo(accept);
+    }
+    if let Some(accept) = draw_answer {
+        game.answer_draw(accept);
+    }
+}
+
+// Navigation buttons of a finished game or a loaded record, with the move list window
+fn draw_replay_controls(game: &mut Game, x: f32, y: f32) {
+    let width = BUTTTON_LENGTH - 30.;
+    let small = (width - 3. * 8.) / 4.;
+    let height = BUTTTON_HEIGTH - 30.;
+
+    // Jump to start, previous, next and jump to end
+    let position = game.rock_move.len();
+    let targets = [
+        0,
+        position.saturating_sub(1),
+        position + 1,
+        game.replay_length(),
+    ];
+    for (index, (label, target)) in ["|<", "<", ">", ">|"].iter().zip(targets).enumerate() {
+        let button = widgets::Button::new(*label)
+            .size(Vec2::new(small, height))
+            .position(Vec2::new(x + index as f32 * (small + 8.), y))
             .ui(&mut root_ui());
-        if undo {
-            game.undo_move();
+        if button {
+            game.replay_autoplay = false;
+            game.jump_to_move(target);
         }
     }
-    if !game.undone_moves.is_empty() {
-        let redo = widgets::Button::new("Redo")
-            .size(Vec2::new((BUTTTON_LENGTH - 40.) / 2., BUTTTON_HEIGTH - 30.))
-            .position(Vec2::new(x + (BUTTTON_LENGTH - 40.) / 2. + 10., y))
-            .ui(&mut root_ui());
-        if redo {
-            game.redo_move();
+
+    let y = y - height - 5.;
+    let autoplay = widgets::Button::new(if game.replay_autoplay {
+        "Pause"
+    } else {
+        "Play"
+    })
+    .size(Vec2::new((width - 8.) / 2., height))
+    .position(Vec2::new(x, y))
+    .ui(&mut root_ui());
+    if autoplay {
+        game.toggle_autoplay();
+    }
+    let move_list = widgets::Button::new(if game.show_move_list { "Hide" } else { "Moves" })
+        .size(Vec2::new((width - 8.) / 2., height))
+        .position(Vec2::new(x + (width - 8.) / 2. + 8., y))
+        .ui(&mut root_ui());
+    if move_list {
+        game.show_move_list = !game.show_move_list;
+    }
+
+    let y = y - height - 5.;
+    let review = widgets::Button::new(if game.show_review {
+        "Hide review"
+    } else {
+        "Review"
+    })
+    .size(Vec2::new(width, height))
+    .position(Vec2::new(x, y))
+    .ui(&mut root_ui());
+    if review {
+        game.show_review = !game.show_review;
+    }
+
+    let move_text = format!("Move {}/{}", game.rock_move.len(), game.replay_length());
+    draw_text(&move_text, x, y - 10., POLICE_SIZE, BLACK);
+
+    if game.show_move_list {
+        draw_move_list(game);
+    }
+    if game.show_review {
+        draw_review_graph(game);
+    }
+}
+
+// Advantage of black after each reviewed move, with the mistakes highlighted
+// -- clicking on the graph goes to the closest move
+fn draw_review_graph(game: &mut Game) {
+    let (left, top) = (BORDER_OFFSET as f32, REVIEW_GRAPH_TOP);
+    let (width, height) = ((GRID_WINDOW_SIZE - 2 * BORDER_OFFSET) as f32, 180.);
+    let middle = top + height / 2.;
+    draw_rectangle(left, top, width, height, WHITE_SEMI);
+    draw_rectangle_lines(left, top, width, height, 2., BLACK);
+    draw_line(left, middle, left + width, middle, 1., GRAY);
+    if game.review.is_empty() {
+        let text = if game.reviewing.is_some() {
+            "Reviewing the game..."
+        } else {
+            "No reviewed moves"
+        };
+        draw_text(text, left + 10., middle - 10., POLICE_SIZE, BLACK);
+        return;
+    }
+
+    let step = width / game.review.len() as f32;
+    let mut previous = (left, middle);
+    for (index, review) in game.review.iter().enumerate() {
+        let point = (
+            left + (index + 1) as f32 * step,
+            middle - review.black_advantage() * (height / 2. - 10.),
+        );
+        draw_line(previous.0, previous.1, point.0, point.1, 2., BLACK);
+        let color = match review.quality {
+            MoveQuality::Blunder => Some(RED),
+            MoveQuality::MissedWin => Some(ORANGE),
+            MoveQuality::Inaccuracy => Some(GOLD),
+            _ => None,
+        };
+        if let Some(color) = color {
+            draw_circle(point.0, point.1, 4., color);
+        }
+        previous = point;
+    }
+    // Current position of the replay
+    let current = left + game.rock_move.len() as f32 * step;
+    draw_line(current, top, current, top + height, 2., BLUE);
+
+    let (mouse_x, mouse_y) = mouse_position();
+    if is_mouse_button_pressed(MouseButton::Left)
+        && (left..=left + width).contains(&mouse_x)
+        && (top..=top + height).contains(&mouse_y)
+        && !root_ui().is_mouse_over(vec2(mouse_x, mouse_y))
+    {
+        game.replay_autoplay = false;
+        game.jump_to_move(((mouse_x - left) / step).round() as usize);
+    }
+}
+
+// Clickable list of all the moves, the board goes to the position after the clicked move
+// Placing a stone during the replay creates a variation, the next moves of the position can be switched
+fn draw_move_list(game: &mut Game) {
+    let moves = game.replay_moves();
+    let position = game.rock_move.len();
+    let next_moves = game.variations.next_moves();
+    let is_main_line = game.variations.is_main_line();
+    let mut target = None;
+    let mut variation = None;
+    let mut promote = false;
+    let mut export = false;
+    root_ui().window(
+        hash!(),
+        vec2(GRID_WINDOW_SIZE as f32 - 230., 10.),
+        vec2(220., GRID_WINDOW_SIZE as f32 - 20.),
+        |ui| {
+            if next_moves.len() > 1 {
+                ui.label(None, "Next moves:");
+                for (index, movement) in next_moves.iter().enumerate() {
+                    let label = format!(
+                        "{} {}",
+                        movement.coordinates,
+                        if index == 0 { "(main)" } else { "" }
+                    );
+                    if ui.button(None, label.as_str()) {
+                        variation = Some(*movement);
+                    }
+                }
+            }
+            if !is_main_line && ui.button(None, "Promote to main line") {
+                promote = true;
+            }
+            if ui.button(None, "Export") {
+                export = true;
+            }
+            ui.separator();
+            let start = format!("{} Start", if position == 0 { ">" } else { " " });
+            if ui.button(None, start.as_str()) {
+                target = Some(0);
+            }
+            for (index, movement) in moves.iter().enumerate() {
+                let label = format!(
+                    "{} {}. {} {}{}",
+                    if index + 1 == position { ">" } else { " " },
+                    index + 1,
+                    if movement.player == Player::Black {
+                        "Black"
+                    } else {
+                        "White"
+                    },
+                    movement.coordinates,
+                    game.move_review(index, movement)
+                        .map_or("", |review| review.quality.symbol())
+                );
+                if ui.button(None, label.as_str()) {
+                    target = Some(index + 1);
+                }
+            }
+        },
+    );
+    if let Some(target) = target {
+        game.replay_autoplay = false;
+        game.jump_to_move(target);
+    }
+    if let Some(movement) = variation {
+        game.switch_variation(movement);
+    }
+    if promote {
+        game.promote_variation();
+    }
+    if export {
+        if let Err(error) = game.export_record() {
+            println!("failed to export the game record: {}", error);
         }
     }
+}
+
+pub fn display_winner(game: &mut Game) {
+    // Display the replay buttons
+    let x = (GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE / 2) as f32 - ((BUTTTON_LENGTH - 30.) / 2.);
+    let y = GRID_WINDOW_SIZE as f32 - 70. - (BUTTTON_HEIGTH * 1.5);
+    draw_replay_controls(game, x, y);
 
     // Display winner text
     let y = y + BUTTTON_HEIGTH / 1.5;
     // Background
     draw_rectangle_lines(x, y, BUTTTON_LENGTH - 30., BUTTTON_HEIGTH - 20., 4., BLACK);
     // Winner text
-    let win_text = if game.winner == Winner::Draw {
+    let win_text = if let Winner::Draw(_) = game.winner {
         "Draw".to_string()
+    } else if game.winner == Winner::None {
+        "Replay".to_string()
     } else {
         format!(
             "{} win !",
-            if game.winner == Winner::Black {
+            if game.winner.player() == Some(Player::Black) {
                 "Black"
             } else {
                 "White"
@@ -607,17 +1458,45 @@ pub fn display_winner(game: &mut Game) {
         x + ((BUTTTON_LENGTH - 30. - text_size.width) / 2.),
         y + (BUTTTON_HEIGTH - 20. - text_size.height), // Should be 2.0 ...
         WIN_FONT_SIZE as f32,
-        if game.winner == Winner::Black {
+        if game.winner.player() == Some(Player::Black) {
             BLACK
-        } else if game.winner == Winner::White {
+        } else if game.winner.player() == Some(Player::White) {
             WHITE
         } else {
             BLUE
         },
     );
+    // Reason of the result under the winner text
+    let reason_text = match &game.winner {
+        Winner::None => None,
+        Winner::Black(reason) | Winner::White(reason) => Some(reason.to_string()),
+        Winner::Draw(reason) => Some(reason.to_string()),
+    };
+    if let Some(reason_text) = reason_text {
+        let text_size = measure_text(&reason_text, None, POLICE_SIZE as u16, 1.);
+        draw_text(
+            &reason_text,
+            x + ((BUTTTON_LENGTH - 30. - text_size.width) / 2.),
+            y + BUTTTON_HEIGTH - 20. + TEXT_OFFSET,
+            POLICE_SIZE,
+            BLACK,
+        );
+    }
 }
 
 pub fn draw_player_choices(game: &mut Game) {
+    // The opponent of a network game makes its choice on its own instance
+    if !game.is_local_turn() {
+        draw_text(
+            "Opponent is choosing",
+            (GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE / 2) as f32 - (BUTTTON_LENGTH - 30.) / 2.,
+            GRID_WINDOW_SIZE as f32 - 70. - BUTTTON_HEIGTH * 1.5,
+            FONT_SIZE as f32,
+            BLACK,
+        );
+        return;
+    }
+
     if !game.placed_three_stones {
         let place_stones = widgets::Button::new("Place 2 Stones")
             .size(Vec2::new(BUTTTON_LENGTH - 30., BUTTTON_HEIGTH - 30.))
@@ -628,7 +1507,7 @@ pub fn draw_player_choices(game: &mut Game) {
             .ui(&mut root_ui());
 
         if place_stones {
-            game.player_place_stone();
+            game.choose_opening(OpeningChoice::PlaceStones);
         }
     }
 
@@ -640,7 +1519,7 @@ pub fn draw_player_choices(game: &mut Game) {
         ))
         .ui(&mut root_ui());
     if play_as_black {
-        game.play_as(Player::Black);
+        game.choose_opening(OpeningChoice::Black);
     }
 
     let play_as_white = widgets::Button::new("Play as White")
@@ -651,7 +1530,7 @@ pub fn draw_player_choices(game: &mut Game) {
         ))
         .ui(&mut root_ui());
     if play_as_white {
-        game.play_as(Player::White);
+        game.choose_opening(OpeningChoice::White);
     }
 }
 
@@ -673,3 +1552,36 @@ pub fn draw_player_remaining_stones(game: &Game) {
         BLACK,
     );
 }
+
+pub fn draw_computer_thinking(game: &mut Game) {
+    // Current best line of the search
+    let line = game.computer_thinking_line();
+    draw_moves_line(game, &line, game.rock_move.len() + 1, true);
+
+    // Thinking indicator
+    if let Some(thinking) = &game.computer_thinking {
+        let thinking_text = format!(
+            "Thinking{} {:.2}s",
+            ".".repeat(1 + (thinking.started.elapsed().as_millis() / 500 % 3) as usize),
+            thinking.started.elapsed().as_secs_f32()
+        );
+        draw_text(
+            &thinking_text,
+            (GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE / 2) as f32 - (BUTTTON_LENGTH - 30.) / 2.,
+            GRID_WINDOW_SIZE as f32 - 70. - BUTTTON_HEIGTH * 1.,
+            FONT_SIZE as f32,
+            BLACK,
+        );
+    }
+
+    let move_now = widgets::Button::new("Move now")
+        .size(Vec2::new(BUTTTON_LENGTH - 30., BUTTTON_HEIGTH - 30.))
+        .position(Vec2::new(
+            (GRID_WINDOW_SIZE + PANEL_WINDOW_SIZE / 2) as f32 - (BUTTTON_LENGTH - 30.) / 2.,
+            GRID_WINDOW_SIZE as f32 - 70. - BUTTTON_HEIGTH * 0.75,
+        ))
+        .ui(&mut root_ui());
+    if move_now {
+        game.computer_move_now();
+    }
+}
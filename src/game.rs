@@ -1,16 +1,44 @@
 use crate::{
-    board::{Board, Coordinates, Move},
-    computer::{Algorithm, Computer},
-    constants::DEPTH,
+    board::{Board, Coordinates, DrawReason, IllegalMove, Move, WinReason},
+    broadcast::{Broadcaster, Event, Spectator, DEFAULT_BROADCAST_ADDRESS},
+    clock::{Clock, TimeControl},
+    computer::{
+        Algorithm, BackgroundAnalysis, BackgroundPlay, Computer, Evaluation, MoveOrdering,
+        SearchOptions, SearchStatistics, Strength, STRENGTH_LEVELS,
+    },
+    constants::{
+        ANALYSIS_DEPTH, DEPTH, DRAW_ACCEPT_SCORE, MONTE_CARLO_DRAW_ACCEPT_RATE,
+        MONTE_CARLO_RESIGN_RATE, OPENING_AREA, REVIEW_DEPTH,
+    },
     heuristic::HEURISTIC,
+    json::{board_json, move_json, player_name, rules_json, Json},
     macros::coord,
+    network::{Message, NetworkGame, OpeningChoice, DEFAULT_ADDRESS, PROTOCOL_VERSION},
     player::Player,
+    record::GameRecord,
+    review::{review_in_background, BackgroundReview, MoveReview},
     rock::Rock,
     rules::RuleSet,
+    search_tree::{SearchTree, DEFAULT_NODE_LIMIT, SEARCH_TREE_FILE},
+    variation::VariationTree,
 };
 use colored::Colorize;
 use rand::Rng;
-use std::time::{Duration, Instant};
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+// Text file written when the game record is exported
+pub const RECORD_FILE: &str = "gomoku_record.txt";
+// Text file of the game in progress, also written when the window is closed
+pub const SAVE_FILE: &str = "gomoku_save.txt";
+// Options of the menus, in the gomoku directory of the user configuration
+pub const SETTINGS_FILE: &str = "settings.txt";
+// JSON lines file of the searches of the computer, when the engine log is enabled
+pub const ENGINE_LOG_FILE: &str = "gomoku_engine_log.jsonl";
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum GameMode {
@@ -20,25 +48,56 @@ pub enum GameMode {
     AvA,
 }
 
-#[derive(Debug)]
-pub enum Difficulty {
-    Easy,
-    Medium,
-    Hard,
-}
-
 #[derive(Debug, PartialEq)]
 pub enum Opening {
     None,
     Swap2,
 }
 
-#[derive(PartialEq)]
+// What the computer did after its search, written in the engine log
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum EngineOutcome {
+    Played(Move),
+    Resigned,
+    // No legal move is left, the game is a draw
+    NoMove,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Winner {
     None,
-    Black,
-    White,
-    Draw,
+    Black(WinReason),
+    White(WinReason),
+    Draw(DrawReason),
+}
+
+impl Winner {
+    pub fn won(player: Player, reason: WinReason) -> Winner {
+        match player {
+            Player::Black => Winner::Black(reason),
+            Player::White => Winner::White(reason),
+        }
+    }
+
+    pub fn player(&self) -> Option<Player> {
+        match self {
+            Winner::Black(_) => Some(Player::Black),
+            Winner::White(_) => Some(Player::White),
+            _ => None,
+        }
+    }
+}
+
+// Analysis of a position, running in the background while the interface is drawn
+pub struct Analysing {
+    pub position: Vec<Coordinates>,
+    pub thinking: BackgroundAnalysis,
+}
+
+// Computer search started on the expected reply of the player
+pub struct Ponder {
+    pub expected: Move,
+    pub thinking: BackgroundPlay,
 }
 
 pub struct Game {
@@ -50,6 +109,9 @@ pub struct Game {
     pub computer_play_as: Player,
     pub rules: RuleSet,
     pub computer: Computer,
+    pub computer_thinking: Option<BackgroundPlay>,
+    pub ponder: bool,
+    pub pondering: Option<Ponder>,
     pub generate_recommended_move: bool,
     pub computer_generated_moves: bool,
     pub computer_expected_moves: Vec<Move>,
@@ -58,19 +120,77 @@ pub struct Game {
     pub computer_average_play_time: f64,
     pub computer_highest_play_time: Duration,
     pub computer_lowest_play_time: Duration,
+    pub computer_statistics: Option<SearchStatistics>,
+    // Visits of each move of the expected line, for the Monte Carlo search
+    pub computer_visits: Vec<u32>,
+    // Moves to the end of the game if the last search found a forced win (or loss if negative)
+    pub computer_mate_in: Option<i32>,
+    // Score of the last move of the computer, from its side
+    pub computer_score: Option<i32>,
+    // The computer gives up when it finds a forced loss
+    pub computer_resigns: bool,
+    // Append the position, candidates and search of each computer move to the log file
+    pub engine_log: bool,
+    pub engine_log_path: String,
+    // Keep the nodes of the negamax and minimax searches to explore them after the move
+    pub record_search_tree: bool,
+    // Score every legal move of the position, in PvP and during a replay
+    pub analysis_mode: bool,
+    // Evaluations of the legal moves of the analysed position, from the best one
+    pub analysis: Vec<Evaluation>,
+    // Moves that led to the analysed position, to analyse it again when it changes
+    pub analysis_position: Option<Vec<Coordinates>>,
+    pub analysing: Option<Analysing>,
+    // Quality of each move of the game, compared to the best move of the engine
+    pub review: Vec<MoveReview>,
+    pub reviewing: Option<BackgroundReview>,
+    pub review_started: bool,
+    pub show_review: bool,
+    // Last refused movement of the player and the reason
+    pub illegal_move: Option<(Coordinates, IllegalMove)>,
     pub current_player: Player,
     pub winner: Winner,
+    // Draw offered by a player of a local game, until it's answered or the turn ends
+    pub draw_offer: Option<Player>,
+    pub draw_declined: bool,
+    pub clock: Clock,
+    pub time_control_index: Option<usize>,
+    // Used instead of the time control of the options when it's given to the command line
+    pub custom_time_control: Option<TimeControl>,
     pub rock_move: Vec<Coordinates>,
     pub computer_moves: f64,
     pub undone_moves: Vec<Move>,
+    // Every move played or explored, the undone moves are the continuation to redo
+    pub variations: VariationTree,
+    // Navigate in a loaded record even if the game is not over
+    pub replaying: bool,
+    pub replay_autoplay: bool,
+    pub replay_speed_index: Option<usize>,
+    pub replay_step_time: Instant,
+    pub show_move_list: bool,
     pub show_computer_generated_moves: bool,
     pub algorithm_index: Option<usize>,
-    pub difficulty_index: Option<usize>,
+    pub ordering_index: Option<usize>,
+    pub radius_index: Option<usize>,
+    pub threats_only: bool,
+    pub beam_width_index: Option<usize>,
+    pub move_limit_index: Option<usize>,
+    // Strength level of the computer in the PvA games, from 0 for the level 1
+    pub strength_index: Option<usize>,
     pub opening_index: Option<usize>,
     pub completed_opening: bool,
     pub ask_player_choice: bool,
     pub player_place_stones: usize,
     pub placed_three_stones: bool,
+    // Game against another instance, hosted or joined at the address
+    pub network: Option<NetworkGame>,
+    pub network_address: String,
+    // Events of the games sent to the spectators
+    pub broadcast: Option<Broadcaster>,
+    // Address or `file:path` of the broadcast, also used to watch one
+    pub broadcast_address: String,
+    // Games followed from the broadcast of another instance
+    pub spectator: Option<Spectator>,
 }
 
 impl Default for Game {
@@ -84,6 +204,9 @@ impl Default for Game {
             computer_play_as: Player::Black,
             rules: RuleSet::default(),
             computer: Computer::default(),
+            computer_thinking: None,
+            ponder: false,
+            pondering: None,
             generate_recommended_move: false,
             computer_generated_moves: false,
             computer_expected_moves: vec![],
@@ -92,19 +215,57 @@ impl Default for Game {
             computer_average_play_time: 0.,
             computer_highest_play_time: Duration::from_millis(0),
             computer_lowest_play_time: Duration::MAX,
+            computer_statistics: None,
+            computer_visits: vec![],
+            computer_mate_in: None,
+            computer_score: None,
+            computer_resigns: true,
+            engine_log: false,
+            engine_log_path: ENGINE_LOG_FILE.to_string(),
+            record_search_tree: false,
+            analysis_mode: false,
+            analysis: vec![],
+            analysis_position: None,
+            analysing: None,
+            review: vec![],
+            reviewing: None,
+            review_started: false,
+            show_review: false,
+            illegal_move: None,
             current_player: Player::Black,
             winner: Winner::None,
+            draw_offer: None,
+            draw_declined: false,
+            clock: Clock::default(),
+            time_control_index: Some(0),
+            custom_time_control: None,
             rock_move: vec![],
             computer_moves: 0.,
             undone_moves: vec![],
+            variations: VariationTree::default(),
+            replaying: false,
+            replay_autoplay: false,
+            replay_speed_index: Some(1),
+            replay_step_time: Instant::now(),
+            show_move_list: false,
             show_computer_generated_moves: true,
             algorithm_index: Some(0),
-            difficulty_index: Some(1),
+            ordering_index: Some(3),
+            radius_index: Some(0),
+            threats_only: false,
+            beam_width_index: Some(0),
+            move_limit_index: Some(0),
+            strength_index: Some(4),
             opening_index: Some(0),
             completed_opening: false,
             ask_player_choice: false,
             player_place_stones: 0,
             placed_three_stones: false,
+            network: None,
+            network_address: DEFAULT_ADDRESS.to_string(),
+            broadcast: None,
+            broadcast_address: DEFAULT_BROADCAST_ADDRESS.to_string(),
+            spectator: None,
         }
     }
 }
@@ -117,7 +278,9 @@ impl Game {
         self.mode = GameMode::None;
         self.player_color = Rock::None;
         self.computer_play_as = Player::Black;
-        self.computer = Computer::default();
+        self.computer = self.new_computer();
+        self.cancel_computer_thinking();
+        self.cancel_pondering();
         self.computer_generated_moves = false;
         self.computer_expected_moves = vec![];
         self.play_time = Instant::now();
@@ -125,12 +288,30 @@ impl Game {
         self.computer_average_play_time = 0.;
         self.computer_highest_play_time = Duration::from_millis(0);
         self.computer_lowest_play_time = Duration::MAX;
+        self.computer_statistics = None;
+        self.computer_visits = vec![];
+        self.computer_mate_in = None;
+        self.computer_score = None;
+        self.analysis = vec![];
+        self.analysis_position = None;
+        self.cancel_analysis();
+        self.cancel_review();
+        self.review = vec![];
+        self.review_started = false;
+        self.show_review = false;
+        self.illegal_move = None;
         self.current_player = Player::Black;
         self.winner = Winner::None;
+        self.draw_offer = None;
+        self.draw_declined = false;
+        self.clock = Clock::new(self.time_control());
         self.rock_move = vec![];
         self.computer_moves = 0.;
         self.undone_moves = vec![];
-        self.difficulty_index = Some(1);
+        self.variations = VariationTree::default();
+        self.replaying = false;
+        self.replay_autoplay = false;
+        self.show_move_list = false;
         self.completed_opening = false;
         self.ask_player_choice = false;
         self.player_place_stones = 0;
@@ -142,9 +323,11 @@ impl Game {
         if self.rules.game_ending_capture && !self.rules.capture {
             self.rules.game_ending_capture = false;
         }
+        self.rules.move_limit = self.move_limit();
         self.player_color = color;
         self.mode = GameMode::PvA;
         self.playing = true;
+        self.broadcast_start();
         if color == Rock::Black {
             self.computer_play_as = Player::White;
             if self.opening() == Opening::Swap2 {
@@ -157,6 +340,7 @@ impl Game {
                 self.ask_player_choice = true;
             }
         }
+        self.update_clock();
     }
 
     pub fn start(&mut self, mode: GameMode) {
@@ -164,6 +348,7 @@ impl Game {
         if self.rules.game_ending_capture && !self.rules.capture {
             self.rules.game_ending_capture = false;
         }
+        self.rules.move_limit = self.move_limit();
         self.mode = mode;
         println!(
             "Starting a game [{:#?}] ({:#?}) with rules: {:#?} opening {:#?}",
@@ -173,6 +358,7 @@ impl Game {
             self.opening()
         );
         self.playing = true;
+        self.broadcast_start();
         if self.opening() == Opening::Swap2 {
             // Place the first 3 stones in the main loop if a human player plays first
             if self.mode == GameMode::PvP {
@@ -187,42 +373,67 @@ impl Game {
                 // Option 3, place more stone and resume thegame
                 if rand == 3 {
                     // These stones are randomly generated
-                    let coordinates = coord!(8, 8);
-                    self.board.set_move(
-                        &self.rules,
-                        &Move {
-                            coordinates,
-                            player: Player::White,
-                        },
-                    );
-                    self.rock_move.push(coordinates);
-                    let coordinates = coord!(10, 10);
-                    self.board.set_move(
-                        &self.rules,
-                        &Move {
-                            coordinates,
-                            player: Player::Black,
-                        },
-                    );
-                    self.rock_move.push(coordinates);
+                    let movement = Move {
+                        coordinates: coord!(8, 8),
+                        player: Player::White,
+                    };
+                    self.board.set_move(&self.rules, &movement);
+                    self.record_move(&movement);
+                    let movement = Move {
+                        coordinates: coord!(10, 10),
+                        player: Player::Black,
+                    };
+                    self.board.set_move(&self.rules, &movement);
+                    self.record_move(&movement);
                 }
                 // rand 1 and 2 change the player that play, but in AvA mode it does nothing
                 self.completed_opening = true;
             }
         }
+        self.update_clock();
     }
 
-    pub fn player_won(&mut self) {
+    // Stop the clock and the searches, the result is final
+    fn end_game(&mut self, winner: Winner) {
+        self.clock.end_turn();
+        self.cancel_computer_thinking();
+        self.cancel_pondering();
         self.computer_expected_moves = vec![];
-        self.winner = match self.current_player {
-            Player::Black => Winner::Black,
-            Player::White => Winner::White,
+        self.draw_offer = None;
+        self.winner = winner;
+        self.broadcast_result();
+    }
+
+    // The player to move won on the board
+    pub fn player_won(&mut self) {
+        let reason = self
+            .board
+            .win_reason(&self.rules, self.current_player)
+            .unwrap_or(WinReason::FiveInARow);
+        self.end_game(Winner::won(self.current_player, reason));
+    }
+
+    // The player gives up and the opponent wins
+    pub fn resign_player(&mut self, player: Player) {
+        println!("{:?} resigns", player);
+        self.end_game(Winner::won(player.opponent(), WinReason::Resignation));
+    }
+
+    // Surrender of the player to move, or of the player of this instance in a network game
+    // -- in PvA it's always the player, the computer could be thinking
+    pub fn resign(&mut self) {
+        let player = match &self.network {
+            Some(network) => network.local_player,
+            None if self.mode == GameMode::PvA => self.computer_play_as.opponent(),
+            None => self.current_player,
         };
+        self.send_network(Message::Resign);
+        self.resign_player(player);
     }
 
-    pub fn game_draw(&mut self) {
-        self.computer_expected_moves = vec![];
-        self.winner = Winner::Draw;
+    pub fn game_draw(&mut self, reason: DrawReason) {
+        println!("draw: {}", reason);
+        self.end_game(Winner::Draw(reason));
     }
 
     pub fn next_player(&mut self) {
@@ -233,97 +444,176 @@ impl Game {
         }
         self.previous_play_time = self.play_time.elapsed();
         self.computer_generated_moves = false;
+        self.draw_offer = None;
+        self.draw_declined = false;
         // Check draw
-        if !self.board.player_can_play(&self.rules, self.current_player) {
-            self.game_draw()
+        if let Some(reason) = self.board.draw_reason(&self.rules, self.current_player) {
+            self.game_draw(reason)
         }
         self.play_time = Instant::now();
+        self.update_clock();
+        self.broadcast_clock();
+    }
+
+    // * Clock
+
+    fn time_control(&self) -> TimeControl {
+        if let Some(time_control) = self.custom_time_control {
+            return time_control;
+        }
+        let minutes = |minutes: u64| Duration::from_secs(minutes * 60);
+        match self.time_control_index.unwrap_or_default() {
+            1 => TimeControl::SuddenDeath(minutes(5)),
+            2 => TimeControl::SuddenDeath(minutes(10)),
+            3 => TimeControl::Fischer {
+                time: minutes(3),
+                increment: Duration::from_secs(2),
+            },
+            4 => TimeControl::Fischer {
+                time: minutes(5),
+                increment: Duration::from_secs(3),
+            },
+            5 => TimeControl::ByoYomi {
+                time: minutes(5),
+                periods: 5,
+                period: Duration::from_secs(30),
+            },
+            _ => TimeControl::Unlimited,
+        }
+    }
+
+    // Run the clock of the current player and end the game when a player is out of time
+    pub fn update_clock(&mut self) {
+        if self.winner != Winner::None || self.replaying {
+            return;
+        }
+        if self.clock.turn_player() != Some(self.current_player) {
+            if let Some(player) = self.clock.end_turn() {
+                self.lose_on_time(player);
+                return;
+            }
+            self.clock.start_turn(self.current_player);
+        } else if self.clock.is_flagged(self.current_player) {
+            self.clock.end_turn();
+            self.lose_on_time(self.current_player);
+        }
+    }
+
+    fn lose_on_time(&mut self, player: Player) {
+        println!("{}", format!("{:?} lost on time", player).red());
+        self.end_game(Winner::won(player.opponent(), WinReason::Time));
     }
 
     pub fn play_opening(&mut self, coordinates: Coordinates) {
-        if self.board.get(coordinates.x, coordinates.y) == Rock::None {
-            let movement = Move {
-                coordinates,
-                player: self.current_player,
-            };
-            if self.board.is_move_legal(&self.rules, &movement) {
-                self.board.set_move(&self.rules, &movement);
-                self.rock_move.push(coordinates);
-                self.next_player();
-                self.player_place_stones -= 1;
-                if self.player_place_stones == 0 {
-                    // In PvA if the player placed 3 stones
-                    // -- the computer randomly select an option
-                    // -- and either pass the choice to the player or make the game resume
-                    if self.mode == GameMode::PvA {
-                        let mut rng = rand::thread_rng();
-                        // Computer choose to play as black or white
-                        if self.computer_play_as == Player::Black {
-                            self.placed_three_stones = true;
-                            let rand = rng.gen_range(1..=2);
-                            if rand == 1 {
-                                println!("Computer will play as Black");
-                                self.computer_play_as = Player::Black;
-                            } else {
-                                println!("Computer will play as White");
-                                self.computer_play_as = Player::White;
-                            }
-                            self.completed_opening = true;
-                        }
-                        // Computer choose to play or randomly play 2 stones and givee the choice back to the player
-                        else if self.computer_play_as == Player::White {
-                            let rand = rng.gen_range(1..=3);
-                            // Option 1 and 2 resume the game
-                            if rand == 1 {
-                                println!("Computer will play as Black");
-                                self.computer_play_as = Player::Black;
-                                self.completed_opening = true;
-                            } else if rand == 2 {
-                                println!("Computer will play as White");
-                                self.computer_play_as = Player::White;
-                                self.completed_opening = true;
-                            }
-                            // Option 3, place more stone and ask the player
-                            else {
-                                self.place_random_stones(Player::White, 2);
-                                self.placed_three_stones = true;
-                                self.ask_player_choice = true;
-                            }
-                        }
+        let movement = Move {
+            coordinates,
+            player: self.current_player,
+        };
+        if let Some(reason) = self
+            .board
+            .illegal_opening_move_reason(&self.rules, &movement)
+        {
+            self.refuse_move(&movement, reason);
+            return;
+        }
+        self.illegal_move = None;
+        self.board.set_move(&self.rules, &movement);
+        self.record_move(&movement);
+        self.next_player();
+        self.player_place_stones -= 1;
+        if self.player_place_stones == 0 {
+            // In PvA if the player placed 3 stones
+            // -- the computer randomly select an option
+            // -- and either pass the choice to the player or make the game resume
+            if self.mode == GameMode::PvA {
+                let mut rng = rand::thread_rng();
+                // Computer choose to play as black or white
+                if self.computer_play_as == Player::Black {
+                    self.placed_three_stones = true;
+                    let rand = rng.gen_range(1..=2);
+                    if rand == 1 {
+                        println!("Computer will play as Black");
+                        self.computer_play_as = Player::Black;
+                    } else {
+                        println!("Computer will play as White");
+                        self.computer_play_as = Player::White;
                     }
-                    // In PvP, the first 3 placed stones then ask the second player
-                    // -- if he choose to place 2 stones again, it will then limit to select black or white
-                    else if self.mode == GameMode::PvP {
+                    self.completed_opening = true;
+                }
+                // Computer choose to play or randomly play 2 stones and givee the choice back to the player
+                else if self.computer_play_as == Player::White {
+                    let rand = rng.gen_range(1..=3);
+                    // Option 1 and 2 resume the game
+                    if rand == 1 {
+                        println!("Computer will play as Black");
+                        self.computer_play_as = Player::Black;
+                        self.completed_opening = true;
+                    } else if rand == 2 {
+                        println!("Computer will play as White");
+                        self.computer_play_as = Player::White;
+                        self.completed_opening = true;
+                    }
+                    // Option 3, place more stone and ask the player
+                    else {
+                        self.place_random_stones(Player::White, 2);
+                        self.placed_three_stones = true;
                         self.ask_player_choice = true;
                     }
                 }
             }
+            // In PvP, the first 3 placed stones then ask the second player
+            // -- if he choose to place 2 stones again, it will then limit to select black or white
+            else if self.mode == GameMode::PvP {
+                self.ask_player_choice = true;
+            }
         }
     }
 
+    // Remember why a movement of the player was refused to display it
+    fn refuse_move(&mut self, movement: &Move, reason: IllegalMove) {
+        println!("{} {}: {}", "illegal move".red(), movement, reason);
+        self.illegal_move = Some((movement.coordinates, reason));
+    }
+
     pub fn play_player(&mut self, coordinates: Coordinates) {
-        if self.board.get(coordinates.x, coordinates.y) == Rock::None {
-            let movement = Move {
-                coordinates,
-                player: self.current_player,
-            };
-            if self.board.is_move_legal(&self.rules, &movement) {
-                let captures = self.board.set_move(&self.rules, &movement);
+        let movement = Move {
+            coordinates,
+            player: self.current_player,
+        };
+        match self.play_player_quietly(coordinates) {
+            Ok(captures) => {
                 println!(
                     "player played: {} with a score of {}",
                     movement,
                     HEURISTIC.movement_score(&self.rules, &self.board, &movement, captures),
                 );
-                self.computer_generated_moves = false;
-                self.rock_move.push(coordinates);
-                if self.board.is_winning(&self.rules, movement.player) {
-                    self.player_won();
-                } else {
-                    self.next_player();
-                }
                 println!("{}", self.board);
             }
+            Err(reason) => self.refuse_move(&movement, reason),
+        }
+    }
+
+    // Same as play_player without printing the move and the board, for the engine server
+    // -- returns the number of captured rocks or the reason the move is refused
+    pub fn play_player_quietly(&mut self, coordinates: Coordinates) -> Result<u8, IllegalMove> {
+        let movement = Move {
+            coordinates,
+            player: self.current_player,
+        };
+        if let Some(reason) = self.board.illegal_move_reason(&self.rules, &movement) {
+            return Err(reason);
         }
+        self.illegal_move = None;
+        let captures = self.board.set_move(&self.rules, &movement);
+        self.computer_generated_moves = false;
+        self.record_move(&movement);
+        if self.board.is_winning(&self.rules, movement.player) {
+            self.player_won();
+        } else {
+            self.resolve_pondering(&movement);
+            self.next_player();
+        }
+        Ok(captures)
     }
 
     pub fn opening(&self) -> Opening {
@@ -334,30 +624,77 @@ impl Game {
         }
     }
 
-    fn algorithm(&self) -> Algorithm {
-        let index = self.algorithm_index.unwrap_or_default();
-        match index {
-            2 => Algorithm::Greedy,
-            1 => Algorithm::Minimax,
-            _ => Algorithm::Negamax,
+    pub fn algorithm(&self) -> Algorithm {
+        Algorithm::ALL
+            .get(self.algorithm_index.unwrap_or_default())
+            .copied()
+            .unwrap_or(Algorithm::Negamax)
+    }
+
+    fn ordering(&self) -> MoveOrdering {
+        let index = self.ordering_index.unwrap_or_default();
+        MoveOrdering::ALL[index.min(MoveOrdering::ALL.len() - 1)]
+    }
+
+    fn search_options(&self) -> SearchOptions {
+        SearchOptions {
+            ordering: self.ordering(),
+            radius: self.radius_index.unwrap_or_default() as i16 + 1,
+            threats_only: self.threats_only,
+            beam_width: match self.beam_width_index.unwrap_or_default() {
+                1 => 5,
+                2 => 10,
+                3 => 20,
+                _ => 0,
+            },
         }
     }
 
-    fn difficulty(&self) -> Difficulty {
-        let index = self.difficulty_index.unwrap_or_default();
-        match index {
-            2 => Difficulty::Hard,
-            0 => Difficulty::Easy,
-            _ => Difficulty::Medium,
+    fn move_limit(&self) -> Option<u16> {
+        match self.move_limit_index.unwrap_or_default() {
+            1 => Some(100),
+            2 => Some(200),
+            3 => Some(300),
+            _ => None,
+        }
+    }
+
+    fn new_computer(&self) -> Computer {
+        Computer {
+            options: self.search_options(),
+            tree: self
+                .record_search_tree
+                .then(|| SearchTree::new(DEFAULT_NODE_LIMIT)),
+            ..Default::default()
+        }
+    }
+
+    pub fn strength(&self) -> Strength {
+        Strength::level(self.strength_index.unwrap_or_default() + 1)
+    }
+
+    // The strength of a PvA game narrows the search and adds the mistakes of the weaker levels
+    fn with_strength(&self, mut computer: Computer) -> Computer {
+        if self.mode == GameMode::PvA {
+            let strength = self.strength();
+            computer.options.beam_width = match computer.options.beam_width {
+                0 => strength.beam_width,
+                width => width.min(strength.beam_width),
+            };
+            computer.randomness = strength.randomness;
         }
+        computer
     }
 
-    fn difficulty_depth(&self) -> usize {
-        match self.difficulty() {
-            Difficulty::Hard => DEPTH + 2,
-            Difficulty::Medium => DEPTH.max(1),
-            Difficulty::Easy => (DEPTH - 2).max(1),
+    // Time given by the clock, and by the strength in a PvA game
+    // -- the randomization of the weaker strengths happens in the same time
+    fn search_budget(&self) -> Option<Duration> {
+        let budget = self.clock.search_budget(self.current_player);
+        if self.mode != GameMode::PvA {
+            return budget;
         }
+        let time = self.strength().time;
+        Some(budget.map_or(time, |budget| budget.min(time)))
     }
 
     pub fn generate_computer_recommended_moves(&mut self) {
@@ -379,12 +716,56 @@ impl Game {
         self.computer_generated_moves = true;
     }
 
+    // Score all the legal moves of the current position, once for each position
+    // -- the analysis runs in the background and is checked on each frame
+    pub fn analyse_position(&mut self) {
+        if let Some(analysing) = self.analysing.take() {
+            if analysing.position != self.rock_move {
+                // The position changed, the next one is analysed instead
+                analysing.thinking.cancel();
+            } else if analysing.thinking.is_finished() {
+                match analysing.thinking.join() {
+                    Ok(analysis) => self.analysis = analysis,
+                    Err(error) => {
+                        println!("{} {}", "analysis failed:".red(), error);
+                        self.analysis = vec![];
+                    }
+                }
+                self.analysis_position = Some(analysing.position);
+                return;
+            } else {
+                self.analysing = Some(analysing);
+                return;
+            }
+        }
+        if self.analysis_position.as_ref() == Some(&self.rock_move) {
+            return;
+        }
+        self.analysing = Some(Analysing {
+            position: self.rock_move.clone(),
+            thinking: self.new_computer().analyse_in_background(
+                self.algorithm(),
+                self.rules,
+                self.board.clone(),
+                ANALYSIS_DEPTH,
+                self.current_player,
+            ),
+        });
+    }
+
+    pub fn cancel_analysis(&mut self) {
+        if let Some(analysing) = self.analysing.take() {
+            analysing.thinking.cancel();
+        }
+    }
+
     pub fn random_opening_coordinates(&self) -> Coordinates {
         let mut rng = rand::thread_rng();
-        let (mut x, mut y) = (rng.gen_range(7..=11), rng.gen_range(7..=11));
+        let area = OPENING_AREA.0..=OPENING_AREA.1;
+        let (mut x, mut y) = (rng.gen_range(area.clone()), rng.gen_range(area.clone()));
         while self.board.get(x, y) != Rock::None {
-            x = rng.gen_range(7..=11);
-            y = rng.gen_range(7..=11);
+            x = rng.gen_range(area.clone());
+            y = rng.gen_range(area.clone());
         }
         coord!(x, y)
     }
@@ -392,15 +773,12 @@ impl Game {
     pub fn place_random_stones(&mut self, starts_with: Player, amount: u8) {
         let mut player = starts_with;
         for _ in 0..amount {
-            let coordinates = self.random_opening_coordinates();
-            self.board.set_move(
-                &self.rules,
-                &Move {
-                    coordinates,
-                    player,
-                },
-            );
-            self.rock_move.push(coordinates);
+            let movement = Move {
+                coordinates: self.random_opening_coordinates(),
+                player,
+            };
+            self.board.set_move(&self.rules, &movement);
+            self.record_move(&movement);
             player = player.opponent();
         }
     }
@@ -431,19 +809,148 @@ impl Game {
         self.placed_three_stones = true;
     }
 
-    pub fn play_computer(&mut self) {
-        let depth = if self.mode == GameMode::PvA {
-            self.difficulty_depth()
+    fn computer_depth(&self) -> usize {
+        if self.mode == GameMode::PvA {
+            self.strength().depth
         } else {
             DEPTH
-        };
-        let play_result = self.computer.play(
+        }
+    }
+
+    #[cfg(feature = "cli_ava")]
+    pub fn play_computer(&mut self) {
+        let computer = std::mem::take(&mut self.computer);
+        let computer = self.with_strength(computer);
+        let thinking = computer.play_in_background(
             self.algorithm(),
-            &self.rules,
-            &mut self.board,
-            depth,
+            self.rules,
+            self.board.clone(),
+            self.computer_depth(),
             self.current_player,
         );
+        // Stop the search when the time given by the clock is used
+        if let Some(budget) = self.search_budget() {
+            while !thinking.is_finished() && thinking.started.elapsed() < budget {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            thinking.move_now();
+        }
+        let (computer, play_result) = thinking.join();
+        self.computer = computer;
+        self.apply_computer_play(play_result);
+    }
+
+    // Start the computer search in the background, or play its move if it's done
+    // -- the interface keeps being drawn while the computer is thinking
+    pub fn play_computer_in_background(&mut self) {
+        if let Some(thinking) = self.computer_thinking.take() {
+            if thinking.is_finished() {
+                let (computer, play_result) = thinking.join();
+                self.computer = computer;
+                self.apply_computer_play(play_result);
+            } else {
+                // Play the best move found so far when the time given by the clock is used
+                if let Some(budget) = self.search_budget() {
+                    if thinking.started.elapsed() >= budget {
+                        thinking.move_now();
+                    }
+                }
+                self.computer_thinking = Some(thinking);
+            }
+        } else {
+            let computer = std::mem::take(&mut self.computer);
+            let computer = self.with_strength(computer);
+            self.computer_thinking = Some(computer.play_in_background(
+                self.algorithm(),
+                self.rules,
+                self.board.clone(),
+                self.computer_depth(),
+                self.current_player,
+            ));
+        }
+    }
+
+    // Stop the background search, the best move found so far is played on the next frame
+    pub fn computer_move_now(&mut self) {
+        if let Some(thinking) = &self.computer_thinking {
+            thinking.move_now();
+        }
+    }
+
+    // The thread is left to finish on it's own and it's result is ignored
+    pub fn cancel_computer_thinking(&mut self) {
+        if let Some(thinking) = self.computer_thinking.take() {
+            thinking.move_now();
+        }
+    }
+
+    // Search the position after the expected reply of the player while they are thinking
+    // -- the expected reply is the first move of the last computer line that is not played yet
+    pub fn ponder(&mut self) {
+        if !self.ponder || self.pondering.is_some() || self.computer_thinking.is_some() {
+            return;
+        }
+        let expected = match self.computer_expected_moves.iter().find(|movement| {
+            self.board
+                .get(movement.coordinates.x, movement.coordinates.y)
+                == Rock::None
+        }) {
+            Some(expected) => *expected,
+            None => return,
+        };
+        if expected.player != self.current_player
+            || !self.board.is_move_legal(&self.rules, &expected)
+        {
+            return;
+        }
+        let mut board = self.board.clone();
+        board.set_move(&self.rules, &expected);
+        if board.is_winning(&self.rules, expected.player) {
+            return;
+        }
+        println!("computer pondering on {}", expected);
+        self.pondering = Some(Ponder {
+            expected,
+            thinking: self.with_strength(self.new_computer()).play_in_background(
+                self.algorithm(),
+                self.rules,
+                board,
+                self.computer_depth(),
+                self.computer_play_as,
+            ),
+        });
+    }
+
+    // Keep the pondering search if the player made the expected move, or discard it
+    fn resolve_pondering(&mut self, movement: &Move) {
+        if let Some(pondering) = self.pondering.take() {
+            if pondering.expected == *movement {
+                println!("{}", "ponder hit".green());
+                self.cancel_computer_thinking();
+                self.computer_thinking = Some(pondering.thinking);
+            } else {
+                println!("{}", "ponder miss".yellow());
+                pondering.thinking.move_now();
+            }
+        }
+    }
+
+    pub fn cancel_pondering(&mut self) {
+        if let Some(pondering) = self.pondering.take() {
+            pondering.thinking.move_now();
+        }
+    }
+
+    // Best line found by the computer that is thinking in the background
+    pub fn computer_thinking_line(&self) -> Vec<Move> {
+        self.computer_thinking
+            .as_ref()
+            .and_then(|thinking| thinking.control.best())
+            .map(|evaluation| evaluation.movements)
+            .unwrap_or_default()
+    }
+
+    pub fn apply_computer_play(&mut self, play_result: Result<Evaluation, String>) {
         if let Ok(play) = play_result {
             // Collect times
             let play_time = self.play_time.elapsed();
@@ -459,6 +966,24 @@ impl Game {
                 self.computer_lowest_play_time = play_time;
             }
             // Handle the movement
+            println!("computer search: {}", play.statistics);
+            self.computer_mate_in = play.mate_in();
+            self.computer_score = Some(play.score);
+            self.broadcast(Event::Evaluation {
+                score: play.score,
+                mate_in: play.mate_in(),
+                line: play.movements.clone(),
+            });
+            if self.computer_resigns && self.computer_is_lost(&play) {
+                self.computer_statistics = Some(play.statistics);
+                self.computer_expected_moves = play.movements;
+                self.log_engine_move(EngineOutcome::Resigned, play_time);
+                self.save_search_tree();
+                self.resign_player(self.current_player);
+                return;
+            }
+            self.computer_statistics = Some(play.statistics);
+            self.computer_visits = play.visits;
             self.computer_expected_moves = play.movements;
             let mut next_move = self.computer_expected_moves.first();
             if next_move.is_none() {
@@ -470,15 +995,21 @@ impl Game {
                     next_move = self.computer_expected_moves.first();
                 }
             }
+            let next_move = next_move.copied();
+            self.log_engine_move(
+                next_move.map_or(EngineOutcome::NoMove, EngineOutcome::Played),
+                play_time,
+            );
+            self.save_search_tree();
             if let Some(movement) = next_move {
-                let captures = self.board.set_move(&self.rules, movement);
+                let captures = self.board.set_move(&self.rules, &movement);
                 println!(
                     "computer played: {} with a score of {} in {}ms",
                     movement,
-                    HEURISTIC.movement_score(&self.rules, &self.board, movement, captures),
+                    HEURISTIC.movement_score(&self.rules, &self.board, &movement, captures),
                     play_time.as_millis()
                 );
-                self.rock_move.push(movement.coordinates);
+                self.record_move(&movement);
                 self.computer_moves += 1.;
                 if self.board.is_winning(&self.rules, movement.player) {
                     self.player_won();
@@ -486,7 +1017,7 @@ impl Game {
                     self.next_player();
                 }
             } else {
-                self.game_draw();
+                self.game_draw(DrawReason::NoLegalMove(self.current_player));
             }
             println!("{}", self.board);
         } else {
@@ -494,6 +1025,13 @@ impl Game {
         }
     }
 
+    // Every movement played on the board is also added to the variations
+    fn record_move(&mut self, movement: &Move) {
+        self.rock_move.push(movement.coordinates);
+        self.variations.play(*movement);
+        self.broadcast(Event::Move(*movement));
+    }
+
     pub fn undo_move(&mut self) {
         if let Some(last_coordinates) = self.rock_move.pop() {
             let undone_move = Move {
@@ -506,7 +1044,9 @@ impl Game {
             };
             self.board.undo_move(&self.rules, &undone_move);
             self.undone_moves.push(undone_move);
+            self.variations.back();
             self.current_player = self.current_player.opponent();
+            self.broadcast(Event::Undo);
         }
     }
 
@@ -514,8 +1054,1130 @@ impl Game {
         let last_move = self.undone_moves.pop();
         if let Some(last_move) = last_move {
             self.board.set_move(&self.rules, &last_move);
-            self.rock_move.push(last_move.coordinates);
+            self.record_move(&last_move);
             self.current_player = self.current_player.opponent();
         }
     }
+
+    // * Network
+
+    // Wait for an opponent on the address, the game starts when it joins
+    pub fn host_network_game(&mut self) {
+        self.network = Some(NetworkGame::host(&self.network_address));
+    }
+
+    // Connect to a host, the game starts when its rules are received
+    pub fn join_network_game(&mut self) {
+        self.network = Some(NetworkGame::join(&self.network_address));
+    }
+
+    // Start a PvP game without closing the connection
+    fn start_network_game(&mut self) {
+        let network = self.network.take();
+        self.start(GameMode::PvP);
+        self.network = network;
+    }
+
+    // Always true without network, the opponent plays its own moves and opening choices
+    pub fn is_local_turn(&self) -> bool {
+        let Some(network) = &self.network else {
+            return true;
+        };
+        if !network.is_connected() {
+            false
+        } else if self.opening() != Opening::None && !self.completed_opening {
+            // The host places the first stones and makes the last choice
+            network.is_host == ((self.player_place_stones > 0) != self.placed_three_stones)
+        } else {
+            self.current_player == network.local_player
+        }
+    }
+
+    // Play a movement of this instance, it's also sent to the opponent in a network game
+    pub fn play_local_move(&mut self, coordinates: Coordinates) {
+        if !self.is_local_turn() {
+            return;
+        }
+        let moves = self.rock_move.len();
+        if self.opening() != Opening::None && !self.completed_opening {
+            self.play_opening(coordinates);
+        } else {
+            self.play_player(coordinates);
+        }
+        if self.rock_move.len() > moves {
+            // Playing a move declines the pending undo request
+            if self
+                .network
+                .as_ref()
+                .is_some_and(|network| network.undo_requested)
+            {
+                self.answer_undo(false);
+            }
+            self.send_network(Message::Move(coordinates));
+        }
+    }
+
+    pub fn choose_opening(&mut self, choice: OpeningChoice) {
+        if !self.is_local_turn() {
+            return;
+        }
+        self.apply_opening_choice(choice, true);
+        self.send_network(Message::Choice(choice));
+    }
+
+    fn apply_opening_choice(&mut self, choice: OpeningChoice, local: bool) {
+        let player = match choice {
+            OpeningChoice::PlaceStones => return self.player_place_stone(),
+            OpeningChoice::Black => Player::Black,
+            OpeningChoice::White => Player::White,
+        };
+        self.play_as(player);
+        if let Some(network) = &mut self.network {
+            network.local_player = if local { player } else { player.opponent() };
+        }
+    }
+
+    fn send_network(&mut self, message: Message) {
+        if let Some(network) = &mut self.network {
+            network.send(message);
+        }
+    }
+
+    fn log_network(&mut self, text: String) {
+        if let Some(network) = &mut self.network {
+            network.log.push(text);
+        }
+    }
+
+    // Start the game when the opponent joined and apply the messages it sent
+    pub fn update_network(&mut self) {
+        let Some(network) = &mut self.network else {
+            return;
+        };
+        if network.accept() {
+            // The opponent plays with the rules of the host
+            self.start_network_game();
+            let hello = Message::Hello {
+                version: PROTOCOL_VERSION,
+                rules: self.rules,
+                opening: self.opening_index.unwrap_or_default(),
+                time_control: self.time_control_index.unwrap_or_default(),
+            };
+            self.send_network(hello);
+            self.log_network("The opponent joined the game".to_string());
+            return;
+        }
+        let (messages, mut error) = network.receive();
+        for message in messages {
+            if let Err(message_error) = self.apply_network_message(message) {
+                error = Some(message_error);
+                break;
+            }
+        }
+        if let Some(error) = error {
+            if let Some(network) = &mut self.network {
+                network.disconnect(error);
+            }
+        }
+    }
+
+    // Apply a message of the opponent, an error closes the connection
+    fn apply_network_message(&mut self, message: Message) -> Result<(), String> {
+        let in_opening = self.opening() != Opening::None && !self.completed_opening;
+        let running = self.playing && self.winner == Winner::None;
+        match message {
+            Message::Hello {
+                version,
+                rules,
+                opening,
+                time_control,
+            } => {
+                if self.playing || self.network.as_ref().is_some_and(|network| network.is_host) {
+                    return Err("Unexpected rules from the opponent".to_string());
+                }
+                if version != PROTOCOL_VERSION {
+                    return Err(format!(
+                        "The host uses the protocol version {} instead of {}",
+                        version, PROTOCOL_VERSION
+                    ));
+                }
+                self.opening_index = Some(opening);
+                self.time_control_index = Some(time_control);
+                self.start_network_game();
+                self.rules = rules;
+                self.log_network("Joined the game".to_string());
+            }
+            Message::Move(coordinates) => {
+                if !running || self.is_local_turn() {
+                    return Err(format!("Unexpected move {} from the opponent", coordinates));
+                }
+                let movement = Move {
+                    coordinates,
+                    player: self.current_player,
+                };
+                // Both instances check the moves
+                if !coordinates.is_on_board()
+                    || self.board.get(coordinates.x, coordinates.y) != Rock::None
+                    || !self.board.is_move_legal(&self.rules, &movement)
+                    || (in_opening
+                        && self
+                            .board
+                            .illegal_opening_move_reason(&self.rules, &movement)
+                            .is_some())
+                {
+                    return Err(format!("Illegal move {} from the opponent", coordinates));
+                }
+                if in_opening {
+                    self.play_opening(coordinates);
+                } else {
+                    self.play_player(coordinates);
+                }
+            }
+            Message::Choice(choice) => {
+                if !in_opening
+                    || !self.ask_player_choice
+                    || self.is_local_turn()
+                    || (choice == OpeningChoice::PlaceStones && self.placed_three_stones)
+                {
+                    return Err("Unexpected opening choice from the opponent".to_string());
+                }
+                self.apply_opening_choice(choice, false);
+            }
+            Message::UndoRequest => {
+                if self.can_answer_undo() {
+                    if let Some(network) = &mut self.network {
+                        network.undo_requested = true;
+                    }
+                    self.log_network("The opponent asks to take back its move".to_string());
+                } else {
+                    self.send_network(Message::UndoDecline);
+                }
+            }
+            Message::UndoAccept => {
+                let pending = self
+                    .network
+                    .as_mut()
+                    .is_some_and(|network| std::mem::take(&mut network.undo_pending));
+                if !pending
+                    || !running
+                    || in_opening
+                    || self.is_local_turn()
+                    || self.rock_move.is_empty()
+                {
+                    return Err("Unexpected undo from the opponent".to_string());
+                }
+                self.take_back_move();
+                self.log_network("The opponent accepted to take back your move".to_string());
+            }
+            Message::UndoDecline => {
+                if let Some(network) = &mut self.network {
+                    network.undo_pending = false;
+                }
+                self.log_network("The opponent declined to take back your move".to_string());
+            }
+            Message::Resign => {
+                if running {
+                    let player = self.network.as_ref().map(|network| network.local_player);
+                    self.resign_player(player.unwrap_or(self.current_player).opponent());
+                    self.log_network("The opponent resigned".to_string());
+                }
+            }
+            Message::DrawOffer => {
+                if running {
+                    if let Some(network) = &mut self.network {
+                        network.draw_offered = true;
+                    }
+                    self.log_network("The opponent offers a draw".to_string());
+                }
+            }
+            Message::DrawAccept => {
+                let pending = self
+                    .network
+                    .as_mut()
+                    .is_some_and(|network| std::mem::take(&mut network.draw_pending));
+                if !pending {
+                    return Err("Unexpected draw acceptance from the opponent".to_string());
+                }
+                if running {
+                    self.game_draw(DrawReason::Agreement);
+                    self.log_network("The opponent accepted the draw".to_string());
+                }
+            }
+            Message::DrawDecline => {
+                if let Some(network) = &mut self.network {
+                    network.draw_pending = false;
+                }
+                self.log_network("The opponent declined the draw".to_string());
+            }
+            Message::Chat(text) => self.log_network(format!("Opponent: {}", text)),
+        }
+        Ok(())
+    }
+
+    // The last move was played by the opponent, outside of the opening
+    fn can_answer_undo(&self) -> bool {
+        self.playing
+            && self.winner == Winner::None
+            && (self.opening() == Opening::None || self.completed_opening)
+            && !self.rock_move.is_empty()
+            && self.is_local_turn()
+    }
+
+    // The last move was played by this instance
+    pub fn can_request_undo(&self) -> bool {
+        self.network
+            .as_ref()
+            .is_some_and(|network| network.is_connected())
+            && self.playing
+            && self.winner == Winner::None
+            && (self.opening() == Opening::None || self.completed_opening)
+            && !self.rock_move.is_empty()
+            && !self.is_local_turn()
+    }
+
+    fn take_back_move(&mut self) {
+        self.undo_move();
+        // The move stays in the variations but can't be redone
+        self.undone_moves.clear();
+    }
+
+    pub fn request_undo(&mut self) {
+        if self.can_request_undo() {
+            if let Some(network) = &mut self.network {
+                network.undo_pending = true;
+            }
+            self.send_network(Message::UndoRequest);
+            self.log_network("You asked to take back your move".to_string());
+        }
+    }
+
+    pub fn answer_undo(&mut self, accept: bool) {
+        if let Some(network) = &mut self.network {
+            network.undo_requested = false;
+        }
+        if accept && self.can_answer_undo() {
+            self.take_back_move();
+            self.send_network(Message::UndoAccept);
+        } else {
+            self.send_network(Message::UndoDecline);
+        }
+    }
+
+    // Offer a draw to the opponent of the player to move, or of the local player in a network game
+    // -- the computer answers right away from the score of its last move
+    pub fn offer_draw(&mut self) {
+        if self.winner != Winner::None {
+            return;
+        }
+        if let Some(network) = &mut self.network {
+            network.draw_pending = true;
+            self.send_network(Message::DrawOffer);
+            self.log_network("You offered a draw".to_string());
+        } else if self.mode == GameMode::PvA && self.current_player != self.computer_play_as {
+            let accept = self.computer_accepts_draw();
+            println!(
+                "computer {} the draw",
+                if accept { "accepts" } else { "declines" }
+            );
+            self.answer_draw(accept);
+        } else {
+            self.draw_offer = Some(self.current_player);
+        }
+    }
+
+    // The computer only takes a draw when it's not ahead
+    pub fn computer_accepts_draw(&self) -> bool {
+        let accept_score = match self.algorithm() {
+            Algorithm::MonteCarlo => MONTE_CARLO_DRAW_ACCEPT_RATE,
+            _ => DRAW_ACCEPT_SCORE,
+        };
+        self.computer_score
+            .is_some_and(|score| score <= accept_score)
+    }
+
+    // The computer resigns a proven loss, or a position where it almost never wins its playouts
+    fn computer_is_lost(&self, play: &Evaluation) -> bool {
+        match self.algorithm() {
+            Algorithm::MonteCarlo => {
+                !play.movements.is_empty() && play.score <= MONTE_CARLO_RESIGN_RATE
+            }
+            _ => play.mate_in().is_some_and(|moves| moves < 0),
+        }
+    }
+
+    pub fn answer_draw(&mut self, accept: bool) {
+        self.draw_offer = None;
+        if let Some(network) = &mut self.network {
+            network.draw_offered = false;
+        }
+        if accept && self.winner == Winner::None {
+            self.send_network(Message::DrawAccept);
+            self.game_draw(DrawReason::Agreement);
+        } else {
+            self.send_network(Message::DrawDecline);
+            self.draw_declined = true;
+        }
+    }
+
+    pub fn send_chat(&mut self) {
+        let Some(network) = &mut self.network else {
+            return;
+        };
+        let text = network.chat_input.trim().to_string();
+        network.chat_input.clear();
+        if !text.is_empty() {
+            network.log.push(format!("You: {}", text));
+            network.send(Message::Chat(text));
+        }
+    }
+
+    // * Broadcast
+
+    // Stream the games to the spectators, or stop it
+    pub fn toggle_broadcast(&mut self) {
+        if self.broadcast.take().is_none() {
+            self.broadcast = Some(Broadcaster::start(&self.broadcast_address));
+            if self.playing && !self.replaying {
+                self.broadcast_start();
+            }
+        }
+    }
+
+    // Replays and the spectator's own board are not broadcasted
+    // -- once the game ended, navigating in its moves doesn't change the final position
+    fn broadcast(&mut self, event: Event) {
+        if self.replaying || self.spectator.is_some() {
+            return;
+        }
+        if self.winner != Winner::None && matches!(event, Event::Move(_) | Event::Undo) {
+            return;
+        }
+        if let Some(broadcast) = &mut self.broadcast {
+            broadcast.send(event);
+        }
+    }
+
+    // The rules and the moves already played, for a new game or a loaded one
+    fn broadcast_start(&mut self) {
+        self.broadcast(Event::Start {
+            rules: self.rules,
+            time_control: self.clock.control,
+        });
+        for movement in self.variations.line(self.variations.current) {
+            self.broadcast(Event::Move(movement));
+        }
+        self.broadcast_clock();
+    }
+
+    fn broadcast_clock(&mut self) {
+        if !self.clock.is_enabled() || self.winner != Winner::None {
+            return;
+        }
+        let times = [Player::Black, Player::White].map(|player| self.clock.player_time(player));
+        self.broadcast(Event::Clock {
+            times: times.map(|time| time.main),
+            periods: times.map(|time| time.periods),
+        });
+    }
+
+    fn broadcast_result(&mut self) {
+        self.broadcast(Event::End(self.winner.clone()));
+    }
+
+    // Connect the spectators that joined since the last event
+    pub fn update_broadcast(&mut self) {
+        if let Some(broadcast) = &mut self.broadcast {
+            broadcast.accept();
+        }
+    }
+
+    // Follow the broadcast on the goban, the game starts with the first event
+    pub fn watch(&mut self) {
+        self.reset();
+        self.spectator = Some(Spectator::watch(&self.broadcast_address));
+        self.mode = GameMode::AvA;
+        self.completed_opening = true;
+        self.playing = true;
+    }
+
+    pub fn update_spectator(&mut self) {
+        let Some(spectator) = self
+            .spectator
+            .as_ref()
+            .filter(|spectator| spectator.error.is_none())
+        else {
+            return;
+        };
+        let (events, mut error) = spectator.receive();
+        for event in events {
+            if let Err(event_error) = self.apply_spectator_event(event) {
+                error = Some(event_error);
+                break;
+            }
+        }
+        if let (Some(error), Some(spectator)) = (error, &mut self.spectator) {
+            println!("broadcast stopped: {}", error);
+            spectator.stop(error);
+            self.clock.end_turn();
+        }
+    }
+
+    // The board only follows the game played by the other instance,
+    // -- an event that doesn't fit the position stops following it
+    fn apply_spectator_event(&mut self, event: Event) -> Result<(), String> {
+        match event {
+            Event::Start {
+                rules,
+                time_control,
+            } => {
+                self.reset();
+                self.rules = rules;
+                self.clock = Clock::new(time_control);
+                self.mode = GameMode::AvA;
+                self.completed_opening = true;
+                self.playing = true;
+            }
+            Event::Move(movement) => {
+                let coordinates = movement.coordinates;
+                if !coordinates.is_on_board()
+                    || self.board.get(coordinates.x, coordinates.y) != Rock::None
+                {
+                    return Err(format!("Invalid move {} in the broadcast", movement));
+                }
+                self.board.set_move(&self.rules, &movement);
+                self.record_move(&movement);
+                self.undone_moves.clear();
+                self.current_player = movement.player.opponent();
+                self.play_time = Instant::now();
+            }
+            Event::Evaluation {
+                score,
+                mate_in,
+                line,
+            } => {
+                if line
+                    .iter()
+                    .any(|movement| !movement.coordinates.is_on_board())
+                {
+                    return Err("Invalid line in the broadcast".to_string());
+                }
+                if let Some(spectator) = &mut self.spectator {
+                    spectator.score = Some(score);
+                }
+                self.computer_mate_in = mate_in;
+                self.computer_expected_moves = line;
+            }
+            Event::Clock { times, periods } => {
+                for player in [Player::Black, Player::White] {
+                    let time = &mut self.clock.times[player as usize];
+                    time.main = times[player as usize];
+                    time.periods = periods[player as usize];
+                }
+                // The clock of the player to move runs until the next event
+                if self.winner == Winner::None {
+                    self.clock.start_turn(self.current_player);
+                }
+            }
+            Event::Undo => {
+                self.undo_move();
+                self.undone_moves.clear();
+                self.winner = Winner::None;
+            }
+            Event::End(winner) => {
+                self.clock.end_turn();
+                self.computer_expected_moves = vec![];
+                self.winner = winner;
+            }
+        }
+        Ok(())
+    }
+
+    // * Replay
+
+    // All moves of the game, before and after the current position
+    pub fn replay_moves(&self) -> Vec<Move> {
+        let mut moves = self.variations.line(self.variations.current);
+        moves.extend(self.undone_moves.iter().rev());
+        moves
+    }
+
+    pub fn replay_length(&self) -> usize {
+        self.rock_move.len() + self.undone_moves.len()
+    }
+
+    // Navigate to the position after the given number of moves
+    pub fn jump_to_move(&mut self, move_number: usize) {
+        while self.rock_move.len() > move_number {
+            self.undo_move();
+        }
+        while self.rock_move.len() < move_number && !self.undone_moves.is_empty() {
+            self.redo_move();
+        }
+        self.replay_step_time = Instant::now();
+    }
+
+    pub fn jump_to_start(&mut self) {
+        self.jump_to_move(0);
+    }
+
+    pub fn jump_to_end(&mut self) {
+        self.jump_to_move(self.replay_length());
+    }
+
+    pub fn toggle_autoplay(&mut self) {
+        self.replay_autoplay = !self.replay_autoplay;
+        // Restart from the beginning if the replay is already over
+        if self.replay_autoplay && self.undone_moves.is_empty() {
+            self.jump_to_start();
+        }
+        self.replay_step_time = Instant::now();
+    }
+
+    pub fn replay_speed(&self) -> Duration {
+        Duration::from_millis(match self.replay_speed_index.unwrap_or_default() {
+            0 => 250,
+            2 => 1000,
+            3 => 2000,
+            _ => 500,
+        })
+    }
+
+    // Play the next move of the autoplay when it's time
+    pub fn update_replay(&mut self) {
+        if self.replay_autoplay && self.replay_step_time.elapsed() >= self.replay_speed() {
+            self.redo_move();
+            self.replay_step_time = Instant::now();
+            if self.undone_moves.is_empty() {
+                self.replay_autoplay = false;
+            }
+        }
+    }
+
+    // Replay a recorded game from its first move, following its main line
+    pub fn load_record(&mut self, rules: RuleSet, variations: VariationTree) -> Result<(), String> {
+        check_variations(&rules, &variations)?;
+        self.reset();
+        self.rules = rules;
+        self.playing = true;
+        self.replaying = true;
+        self.completed_opening = true;
+        self.variations = variations;
+        self.variations.rewind();
+        self.follow_variation();
+        Ok(())
+    }
+
+    // * Variations
+
+    // Redo the main line of the variation after the current position
+    fn follow_variation(&mut self) {
+        self.undone_moves = self
+            .variations
+            .continuation(self.variations.current)
+            .into_iter()
+            .rev()
+            .collect();
+    }
+
+    // Explore another move from the current position, the previous continuation stays a variation
+    pub fn play_variation(&mut self, coordinates: Coordinates) {
+        let movement = Move {
+            coordinates,
+            player: self.current_player,
+        };
+        if let Some(reason) = self.board.illegal_move_reason(&self.rules, &movement) {
+            self.refuse_move(&movement, reason);
+            return;
+        }
+        self.illegal_move = None;
+        self.replay_autoplay = false;
+        self.board.set_move(&self.rules, &movement);
+        self.record_move(&movement);
+        self.current_player = self.current_player.opponent();
+        self.follow_variation();
+    }
+
+    // Switch to one of the next moves of the current position
+    pub fn switch_variation(&mut self, movement: Move) {
+        self.replay_autoplay = false;
+        self.undone_moves = vec![movement];
+        self.redo_move();
+        self.follow_variation();
+    }
+
+    pub fn promote_variation(&mut self) {
+        self.variations.promote();
+    }
+
+    // * Review
+
+    // Review the moves of a finished game once, or of a loaded record when the review is shown
+    pub fn update_review(&mut self) {
+        if let Some(reviewing) = self.reviewing.take() {
+            if reviewing.is_finished() {
+                match reviewing.join() {
+                    Ok(review) => self.review = review,
+                    Err(error) => println!("{} {}", "review failed:".red(), error),
+                }
+            } else {
+                self.reviewing = Some(reviewing);
+            }
+        } else if !self.review_started && (self.winner != Winner::None || self.show_review) {
+            self.review_started = true;
+            self.reviewing = Some(review_in_background(
+                self.new_computer(),
+                self.algorithm(),
+                self.rules,
+                self.replay_moves(),
+                REVIEW_DEPTH,
+            ));
+        }
+    }
+
+    pub fn cancel_review(&mut self) {
+        if let Some(reviewing) = self.reviewing.take() {
+            reviewing.cancel();
+        }
+    }
+
+    // Review of the movement played at this index, the moves of the variations are not reviewed
+    pub fn move_review(&self, index: usize, movement: &Move) -> Option<&MoveReview> {
+        self.review
+            .get(index)
+            .filter(|review| review.movement == *movement)
+    }
+
+    // Write the rules, the result and all the variations of the game to a text file
+    pub fn export_record(&self) -> std::io::Result<()> {
+        let result = match &self.winner {
+            Winner::None => "*".to_string(),
+            Winner::Black(reason) => format!("Black ({})", reason),
+            Winner::White(reason) => format!("White ({})", reason),
+            Winner::Draw(reason) => format!("Draw ({})", reason),
+        };
+        let mut record = format!(
+            "Capture: {}\nGame ending capture: {}\nNo double three: {}\nMove limit: {}\nResult: {}\n\n{}\n",
+            self.rules.capture,
+            self.rules.game_ending_capture,
+            self.rules.no_double_three,
+            self.rules
+                .move_limit
+                .map_or("None".to_string(), |limit| limit.to_string()),
+            result,
+            self.variations
+        );
+        if !self.review.is_empty() {
+            record.push_str("\nReview:\n");
+            for (index, review) in self.review.iter().enumerate() {
+                record.push_str(&format!(
+                    "{}. {} {}{} {} (score {}, best score {})\n",
+                    index + 1,
+                    if review.movement.player == Player::Black {
+                        "B"
+                    } else {
+                        "W"
+                    },
+                    review.movement.coordinates,
+                    review.quality.symbol(),
+                    review,
+                    review.score,
+                    review.best_score
+                ));
+            }
+        }
+        std::fs::write(RECORD_FILE, record)?;
+        println!("exported the game record to {}", RECORD_FILE);
+        Ok(())
+    }
+
+    // * Engine log
+
+    // Search of the computer in the current position, before its move is played
+    // -- the candidates are the moves of the root in the order they are searched
+    pub fn engine_log_entry(&self, outcome: EngineOutcome, play_time: Duration) -> Json {
+        let statistics = self.computer_statistics.clone().unwrap_or_default();
+        let candidates = self
+            .computer
+            .root_candidates(&self.rules, &self.board, self.current_player)
+            .iter()
+            .map(|candidate| {
+                Json::object(vec![
+                    ("x", (candidate.movement.coordinates.x as i64).into()),
+                    ("y", (candidate.movement.coordinates.y as i64).into()),
+                    ("best_pattern", i64::from(candidate.best_pattern).into()),
+                    (
+                        "score",
+                        i64::from(HEURISTIC.patterns_score(&candidate.pattern_count)).into(),
+                    ),
+                    (
+                        "patterns",
+                        Json::object(
+                            candidate
+                                .pattern_count
+                                .categories()
+                                .into_iter()
+                                .map(|(name, count)| (name, i64::from(count).into()))
+                                .collect(),
+                        ),
+                    ),
+                ])
+            })
+            .collect();
+        Json::object(vec![
+            ("move_number", (self.rock_move.len() as i64 + 1).into()),
+            ("player", player_name(self.current_player).into()),
+            ("algorithm", format!("{:?}", self.algorithm()).into()),
+            ("rules", rules_json(&self.rules)),
+            (
+                "moves",
+                Json::Array(
+                    self.variations
+                        .line(self.variations.current)
+                        .iter()
+                        .map(move_json)
+                        .collect(),
+                ),
+            ),
+            (
+                "captures",
+                Json::object(vec![
+                    ("black", i64::from(self.board.black.captures).into()),
+                    ("white", i64::from(self.board.white.captures).into()),
+                ]),
+            ),
+            ("board", board_json(&self.board)),
+            ("candidates", Json::Array(candidates)),
+            (
+                "played",
+                match &outcome {
+                    EngineOutcome::Played(movement) => move_json(movement),
+                    _ => Json::Null,
+                },
+            ),
+            ("resigned", (outcome == EngineOutcome::Resigned).into()),
+            ("no_move", (outcome == EngineOutcome::NoMove).into()),
+            ("score", self.computer_score.map(i64::from).into()),
+            ("mate_in", self.computer_mate_in.map(i64::from).into()),
+            (
+                "line",
+                Json::Array(self.computer_expected_moves.iter().map(move_json).collect()),
+            ),
+            ("time_ms", (play_time.as_millis() as i64).into()),
+            (
+                "statistics",
+                Json::object(vec![
+                    ("depth", (statistics.depth as i64).into()),
+                    ("max_depth", (statistics.max_depth as i64).into()),
+                    ("nodes", (statistics.nodes as i64).into()),
+                    (
+                        "leaf_evaluations",
+                        (statistics.leaf_evaluations as i64).into(),
+                    ),
+                    ("cutoffs", (statistics.cutoffs as i64).into()),
+                    (
+                        "effective_branching_factor",
+                        statistics.effective_branching_factor().into(),
+                    ),
+                    ("nodes_per_second", statistics.nodes_per_second().into()),
+                    (
+                        "duration_ms",
+                        (statistics.duration.as_millis() as i64).into(),
+                    ),
+                    (
+                        "iterations",
+                        Json::Array(
+                            statistics
+                                .iterations
+                                .iter()
+                                .map(|(depth, duration)| {
+                                    Json::object(vec![
+                                        ("depth", (*depth as i64).into()),
+                                        ("time_ms", (duration.as_millis() as i64).into()),
+                                    ])
+                                })
+                                .collect(),
+                        ),
+                    ),
+                ]),
+            ),
+        ])
+    }
+
+    // Append one line to the engine log, a failure only disables it
+    fn log_engine_move(&mut self, outcome: EngineOutcome, play_time: Duration) {
+        if !self.engine_log {
+            return;
+        }
+        let entry = self.engine_log_entry(outcome, play_time);
+        let written = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.engine_log_path)
+            .and_then(|mut file| writeln!(file, "{}", entry));
+        if let Err(error) = written {
+            println!("{} {}", "engine log stopped:".red(), error);
+            self.engine_log = false;
+        }
+    }
+
+    // Overwritten by each search, explored with the gomoku-tree binary
+    fn save_search_tree(&self) {
+        let Some(tree) = self
+            .computer
+            .tree
+            .as_ref()
+            .filter(|tree| !tree.nodes.is_empty())
+        else {
+            return;
+        };
+        match fs::write(SEARCH_TREE_FILE, tree.to_json().to_string()) {
+            Ok(()) => println!(
+                "saved the search tree of {} nodes to {}",
+                tree.nodes.len(),
+                SEARCH_TREE_FILE
+            ),
+            Err(error) => println!("failed to save the search tree: {}", error),
+        }
+    }
+
+    // * Settings
+
+    // Options of the game, also saved with a game in progress
+    fn settings(&self) -> GameRecord {
+        let mut record = GameRecord {
+            rules: self.rules,
+            ..Default::default()
+        };
+        for (key, index) in [
+            ("algorithm", self.algorithm_index),
+            ("ordering", self.ordering_index),
+            ("radius", self.radius_index),
+            ("beam_width", self.beam_width_index),
+            ("move_limit_option", self.move_limit_index),
+            ("strength", self.strength_index),
+            ("opening", self.opening_index),
+            ("time_control", self.time_control_index),
+            ("replay_speed", self.replay_speed_index),
+        ] {
+            if let Some(index) = index {
+                record.set(key, index);
+            }
+        }
+        for (key, enabled) in [
+            ("threats_only", self.threats_only),
+            ("generate_recommended_move", self.generate_recommended_move),
+            ("analysis_mode", self.analysis_mode),
+            (
+                "show_computer_generated_moves",
+                self.show_computer_generated_moves,
+            ),
+            ("ponder", self.ponder),
+            ("computer_resigns", self.computer_resigns),
+            ("engine_log", self.engine_log),
+            ("record_search_tree", self.record_search_tree),
+        ] {
+            record.set(key, enabled);
+        }
+        record
+    }
+
+    // Missing or invalid settings keep their current value
+    fn apply_settings(&mut self, record: &GameRecord) {
+        self.rules = record.rules;
+        // Number of options of each selector, the values out of range are dropped
+        for (key, index, options) in [
+            ("algorithm", &mut self.algorithm_index, Algorithm::ALL.len()),
+            (
+                "ordering",
+                &mut self.ordering_index,
+                MoveOrdering::ALL.len(),
+            ),
+            ("radius", &mut self.radius_index, 3),
+            ("beam_width", &mut self.beam_width_index, 4),
+            ("move_limit_option", &mut self.move_limit_index, 4),
+            ("strength", &mut self.strength_index, STRENGTH_LEVELS),
+            ("opening", &mut self.opening_index, 2),
+            ("time_control", &mut self.time_control_index, 6),
+            ("replay_speed", &mut self.replay_speed_index, 4),
+        ] {
+            if let Some(value) = record.get::<usize>(key).filter(|value| *value < options) {
+                *index = Some(value);
+            }
+        }
+        for (key, enabled) in [
+            ("threats_only", &mut self.threats_only),
+            (
+                "generate_recommended_move",
+                &mut self.generate_recommended_move,
+            ),
+            ("analysis_mode", &mut self.analysis_mode),
+            (
+                "show_computer_generated_moves",
+                &mut self.show_computer_generated_moves,
+            ),
+            ("ponder", &mut self.ponder),
+            ("computer_resigns", &mut self.computer_resigns),
+            ("engine_log", &mut self.engine_log),
+            ("record_search_tree", &mut self.record_search_tree),
+        ] {
+            if let Some(value) = record.get(key) {
+                *enabled = value;
+            }
+        }
+    }
+
+    // Settings file in the configuration directory of the user
+    fn settings_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|directory| directory.join("gomoku").join(SETTINGS_FILE))
+    }
+
+    pub fn save_settings(&self) {
+        let Some(path) = Game::settings_path() else {
+            return;
+        };
+        if let Some(directory) = path.parent() {
+            if let Err(error) = fs::create_dir_all(directory) {
+                println!("failed to save the settings: {}", error);
+                return;
+            }
+        }
+        if let Err(error) = self.settings().save(&path) {
+            println!("failed to save the settings: {}", error);
+        }
+    }
+
+    // Options of the previous launch, the defaults are kept without a settings file
+    pub fn load_settings(&mut self) {
+        let Some(path) = Game::settings_path().filter(|path| path.exists()) else {
+            return;
+        };
+        match GameRecord::load(&path) {
+            Ok(record) => {
+                self.apply_settings(&record);
+                self.computer = self.new_computer();
+                self.clock = Clock::new(self.time_control());
+            }
+            Err(error) => println!("failed to load the settings: {}", error),
+        }
+    }
+
+    pub fn restore_default_settings(&mut self) {
+        self.apply_settings(&Game::default().settings());
+        self.save_settings();
+    }
+
+    // * Save
+
+    // Write the state of the game in progress to resume it later, with the options used to play it
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let mut record = self.settings();
+        record.variations = self.variations.clone();
+        record.set("mode", format!("{:?}", self.mode));
+        record.set("player_color", format!("{:?}", self.player_color));
+        record.set("computer_play_as", format!("{:?}", self.computer_play_as));
+        record.set("current_player", format!("{:?}", self.current_player));
+        record.set("completed_opening", self.completed_opening);
+        record.set("player_place_stones", self.player_place_stones);
+        record.set("ask_player_choice", self.ask_player_choice);
+        record.set("placed_three_stones", self.placed_three_stones);
+        record.set_moves("undone", &self.undone_moves);
+        for player in [Player::Black, Player::White] {
+            let time = self.clock.player_time(player);
+            let name = format!("{:?}", player).to_lowercase();
+            record.set(&format!("{}_time", name), time.main.as_millis());
+            record.set(&format!("{}_periods", name), time.periods);
+        }
+        record.save(path)?;
+        println!("saved the game to {}", path);
+        Ok(())
+    }
+
+    // Resume a saved game at the position and with the clocks it was saved with
+    pub fn load(&mut self, path: &str) -> Result<(), String> {
+        let record = GameRecord::load(path)?;
+        let player = |key: &str| match record.settings.get(key).map(String::as_str) {
+            Some("White") => Player::White,
+            _ => Player::Black,
+        };
+        self.reset();
+        self.mode = match record.settings.get("mode").map(String::as_str) {
+            Some("PvP") => GameMode::PvP,
+            Some("PvA") => GameMode::PvA,
+            Some("AvA") => GameMode::AvA,
+            _ => return Err(format!("No game in progress in {}", path)),
+        };
+        // The computer and the clock are created from the options
+        self.apply_settings(&record);
+        self.computer = self.new_computer();
+        self.clock = Clock::new(self.time_control());
+        for player in [Player::Black, Player::White] {
+            let name = format!("{:?}", player).to_lowercase();
+            let time = &mut self.clock.times[player as usize];
+            if let Some(main) = record.get(&format!("{}_time", name)) {
+                time.main = Duration::from_millis(main);
+            }
+            if let Some(periods) = record.get(&format!("{}_periods", name)) {
+                time.periods = periods;
+            }
+        }
+        self.player_color = match record.settings.get("player_color").map(String::as_str) {
+            Some("Black") => Rock::Black,
+            Some("White") => Rock::White,
+            _ => Rock::None,
+        };
+        self.computer_play_as = player("computer_play_as");
+        self.current_player = player("current_player");
+        self.completed_opening = record.get("completed_opening").unwrap_or_default();
+        self.player_place_stones = record.get("player_place_stones").unwrap_or_default();
+        self.ask_player_choice = record.get("ask_player_choice").unwrap_or_default();
+        self.placed_three_stones = record.get("placed_three_stones").unwrap_or_default();
+        if let Err(error) = self.replay_record(&record) {
+            self.reset();
+            return Err(error);
+        }
+        self.playing = true;
+        self.update_clock();
+        self.broadcast_start();
+        println!("loaded the game from {}", path);
+        Ok(())
+    }
+
+    // Play again the moves up to the saved position, the file could have been modified
+    fn replay_record(&mut self, record: &GameRecord) -> Result<(), String> {
+        check_variations(&self.rules, &record.variations)?;
+        self.variations = record.variations.clone();
+        let line = self.variations.line(self.variations.current);
+        replay_moves(&self.rules, &mut self.board, &line)?;
+        self.rock_move = line.iter().map(|movement| movement.coordinates).collect();
+        // The undone moves are redone from the last one
+        let undone: Vec<Move> = record.get_moves("undone").unwrap_or_default();
+        let redone: Vec<Move> = undone.iter().rev().copied().collect();
+        replay_moves(&self.rules, &mut self.board.clone(), &redone)?;
+        self.undone_moves = undone;
+        Ok(())
+    }
+}
+
+// Play the moves of a record on the board, the first illegal one is an error
+fn replay_moves(rules: &RuleSet, board: &mut Board, moves: &[Move]) -> Result<(), String> {
+    for movement in moves {
+        if !movement.coordinates.is_on_board() {
+            return Err(format!("The move {} is outside of the board", movement));
+        }
+        if let Some(reason) = board.illegal_move_reason(rules, movement) {
+            return Err(format!("Illegal move {}: {}", movement, reason));
+        }
+        board.set_move(rules, movement);
+    }
+    Ok(())
+}
+
+// Every line of the variations is checked from the start of the game
+fn check_variations(rules: &RuleSet, variations: &VariationTree) -> Result<(), String> {
+    for (node, variation) in variations.nodes.iter().enumerate() {
+        if variation.children.is_empty() {
+            replay_moves(rules, &mut Board::default(), &variations.line(Some(node)))?;
+        }
+    }
+    Ok(())
 }
@@ -1,6 +1,6 @@
 use crate::{
     board::{Board, Move},
-    constants::DIRECTIONS,
+    constants::{DIRECTIONS, MATE_SCORE, WIN_SCORE},
     macros::coord,
     patterns::{Category, PatternCount, PATTERNS},
     player::Player,
@@ -234,12 +234,13 @@ impl Heuristic {
 
     pub fn patterns_score(&self, patterns: &PatternCount) -> i32 {
         // Return maximum value for the best and worst patterns
+        // -- the fours are below the search scores of a forced win
         if patterns.total_captures >= 10 || patterns.five_in_row > 0 {
-            return i32::max_value();
+            return WIN_SCORE;
         } else if patterns.kill_four > 0 {
-            return i32::max_value() - 1;
+            return MATE_SCORE - 1;
         } else if patterns.open_four > 0 {
-            return i32::max_value() - 2;
+            return MATE_SCORE - 2;
         } else if patterns.reveal_capture > 0 && patterns.opponent_captures >= 8 {
             return 0;
         }
@@ -0,0 +1,361 @@
+use crate::{
+    board::{Board, Move},
+    constants::BOARD_SIZE,
+    player::Player,
+    rock::Rock,
+    rules::RuleSet,
+};
+use std::{fmt, str::FromStr};
+
+// Minimal JSON value for the engine server and log, the keys of an object keep their order
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object(pairs: Vec<(&str, Json)>) -> Json {
+        Json::Object(
+            pairs
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(pairs) => pairs
+                .iter()
+                .find(|(other, _)| other == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // Only numbers without a fractional part are integers
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Json::Number(value) if value.fract() == 0. => Some(*value as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<i64> for Json {
+    fn from(value: i64) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self {
+        Json::Number(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for character in value.chars() {
+        match character {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            character if (character as u32) < 0x20 => write!(f, "\\u{:04x}", character as u32)?,
+            character => write!(f, "{}", character)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) if !value.is_finite() => write!(f, "null"),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(pairs) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in pairs.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+pub fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::Black => "black",
+        Player::White => "white",
+    }
+}
+
+pub fn move_json(movement: &Move) -> Json {
+    Json::object(vec![
+        ("player", player_name(movement.player).into()),
+        ("x", (movement.coordinates.x as i64).into()),
+        ("y", (movement.coordinates.y as i64).into()),
+    ])
+}
+
+pub fn rules_json(rules: &RuleSet) -> Json {
+    Json::object(vec![
+        ("capture", rules.capture.into()),
+        ("game_ending_capture", rules.game_ending_capture.into()),
+        ("no_double_three", rules.no_double_three.into()),
+        ("move_limit", rules.move_limit.map(i64::from).into()),
+    ])
+}
+
+// Lines of . B W by row
+pub fn board_json(board: &Board) -> Json {
+    Json::Array(
+        (0..BOARD_SIZE)
+            .map(|y| {
+                (0..BOARD_SIZE)
+                    .map(|x| match board.get(x, y) {
+                        Rock::Black => 'B',
+                        Rock::White => 'W',
+                        Rock::None => '.',
+                    })
+                    .collect::<String>()
+                    .into()
+            })
+            .collect(),
+    )
+}
+
+// Arrays and objects nested deeper are refused instead of overflowing the stack
+const MAX_NESTING: usize = 64;
+
+// Recursive descent parser on the characters of the text
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    // Arrays and objects being parsed
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, expected: &str) -> String {
+        format!("Expected {} at {}", expected, self.position)
+    }
+
+    fn skip_whitespaces(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.position += character.len_utf8();
+        Some(character)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        if self.text[self.position..].starts_with(expected) {
+            self.position += expected.len();
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespaces();
+        match self.peek() {
+            Some('n') => self.expect("null").map(|_| Json::Null),
+            Some('t') => self.expect("true").map(|_| Json::Bool(true)),
+            Some('f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some(character @ ('[' | '{')) => {
+                if self.depth >= MAX_NESTING {
+                    return Err(self.error(&format!("at most {} nested values", MAX_NESTING)));
+                }
+                self.depth += 1;
+                let value = if character == '[' {
+                    self.array()
+                } else {
+                    self.object()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some(character) if character == '-' || character.is_ascii_digit() => self.number(),
+            _ => Err(self.error("a value")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|character| "+-.eE".contains(character) || character.is_ascii_digit())
+        {
+            self.next();
+        }
+        self.text[start..self.position]
+            .parse()
+            .map(Json::Number)
+            .map_err(|_| self.error("a number"))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.next() {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('/') => value.push('/'),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('b') => value.push('\u{8}'),
+                    Some('f') => value.push('\u{c}'),
+                    Some('u') => {
+                        let code = self
+                            .text
+                            .get(self.position..self.position + 4)
+                            .and_then(|code| u32::from_str_radix(code, 16).ok())
+                            .ok_or_else(|| self.error("an unicode escape"))?;
+                        self.position += 4;
+                        value.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    _ => return Err(self.error("an escape")),
+                },
+                Some(character) => value.push(character),
+                None => return Err(self.error("the end of the string")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect("[")?;
+        let mut values = vec![];
+        self.skip_whitespaces();
+        if self.peek() == Some(']') {
+            self.next();
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespaces();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::Array(values)),
+                _ => return Err(self.error(", or ]")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect("{")?;
+        let mut pairs = vec![];
+        self.skip_whitespaces();
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(Json::Object(pairs));
+        }
+        loop {
+            self.skip_whitespaces();
+            let key = self.string()?;
+            self.skip_whitespaces();
+            self.expect(":")?;
+            pairs.push((key, self.value()?));
+            self.skip_whitespaces();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::Object(pairs)),
+                _ => return Err(self.error(", or }")),
+            }
+        }
+    }
+}
+
+impl FromStr for Json {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            text,
+            position: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespaces();
+        if parser.position < text.len() {
+            return Err(parser.error("the end of the text"));
+        }
+        Ok(value)
+    }
+}
@@ -2,11 +2,22 @@
 extern crate lazy_static;
 
 pub mod board;
+pub mod broadcast;
+pub mod clock;
 pub mod computer;
 pub mod constants;
+pub mod game;
 pub mod heuristic;
+pub mod json;
 pub mod macros;
+pub mod network;
 pub mod patterns;
 pub mod player;
+pub mod puzzle;
+pub mod record;
+pub mod review;
 pub mod rock;
 pub mod rules;
+pub mod search_tree;
+pub mod server;
+pub mod variation;
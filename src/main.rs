@@ -1,27 +1,48 @@
 #[macro_use]
 extern crate lazy_static;
 
+#[cfg(not(feature = "cli_puzzles"))]
 use crate::game::{Game, GameMode, Winner};
 
-#[cfg(not(feature = "cli_ava"))]
+#[cfg(not(any(feature = "cli_ava", feature = "cli_puzzles")))]
 use macroquad::prelude::*;
-#[cfg(not(feature = "cli_ava"))]
+#[cfg(not(any(feature = "cli_ava", feature = "cli_puzzles")))]
 use macroquad::ui::{root_ui, Skin};
 
 mod board;
+#[cfg(not(feature = "cli_puzzles"))]
+mod broadcast;
+mod clock;
 mod computer;
 mod constants;
-#[cfg(not(feature = "cli_ava"))]
+#[cfg(not(any(feature = "cli_ava", feature = "cli_puzzles")))]
 mod draw;
+#[cfg(not(feature = "cli_puzzles"))]
 mod game;
 mod heuristic;
+// The accessors of the values are only used by the engine server
+#[allow(dead_code)]
+mod json;
 mod macros;
+#[cfg(not(feature = "cli_puzzles"))]
+mod network;
 mod patterns;
 mod player;
+#[cfg(feature = "cli_puzzles")]
+mod puzzle;
+#[cfg(not(feature = "cli_puzzles"))]
+mod record;
+#[cfg(not(feature = "cli_puzzles"))]
+mod review;
 mod rock;
 mod rules;
+// The explorer is only used by the gomoku-tree binary
+#[allow(dead_code)]
+mod search_tree;
+#[cfg(not(feature = "cli_puzzles"))]
+mod variation;
 
-#[cfg(not(feature = "cli_ava"))]
+#[cfg(not(any(feature = "cli_ava", feature = "cli_puzzles")))]
 fn window_conf() -> Conf {
     use crate::draw::{GRID_WINDOW_SIZE, PANEL_WINDOW_SIZE};
 
@@ -34,16 +55,17 @@ fn window_conf() -> Conf {
     }
 }
 
-#[cfg(not(feature = "cli_ava"))]
+#[cfg(not(any(feature = "cli_ava", feature = "cli_puzzles")))]
 #[macroquad::main(window_conf)]
 async fn main() {
     use crate::{
         draw::{
-            color_selector, display_panel_text, display_winner, draw_goban, draw_player_choices,
-            draw_player_remaining_stones, draw_rock_preview, game_selector, options_selector,
-            GRID_WINDOW_SIZE, SQUARE_SIZE,
+            color_selector, display_panel_text, display_winner, draw_computer_thinking, draw_goban,
+            draw_network, draw_player_choices, draw_player_remaining_stones, draw_rock_preview,
+            draw_spectator, game_selector, network_lobby, options_selector, GRID_WINDOW_SIZE,
+            REVIEW_GRAPH_TOP, SQUARE_SIZE,
         },
-        game::Opening,
+        game::{Opening, SAVE_FILE},
         macros::coord,
         rock::Rock,
     };
@@ -74,15 +96,38 @@ async fn main() {
     root_ui().push_skin(&default_skin);
 
     let mut game = Game::default();
+    game.load_settings();
     let mut b_mouse_pressed: bool = false;
+    // Save the game in progress before closing the window
+    prevent_quit();
 
     loop {
+        if is_quit_requested() {
+            if game.playing
+                && !game.replaying
+                && game.winner == Winner::None
+                && game.network.is_none()
+                && game.spectator.is_none()
+            {
+                if let Err(error) = game.save(SAVE_FILE) {
+                    println!("failed to save the game: {}", error);
+                }
+            }
+            break;
+        }
+        game.update_network();
+        game.update_broadcast();
+        game.update_spectator();
         clear_background(BEIGE);
 
         // Options
         if game.in_options {
             options_selector(&mut game);
         }
+        // Waiting for the opponent of a network game
+        else if !game.playing && game.network.is_some() {
+            network_lobby(&mut game);
+        }
         // Game mode selector
         else if !game.playing {
             if game_selector(&mut game) {
@@ -100,10 +145,46 @@ async fn main() {
             draw_goban(&game);
             display_panel_text(&mut game);
 
-            // Winner
-            if game.winner != Winner::None {
+            // Winner and replay of a finished game or a loaded record
+            if game.winner != Winner::None || game.replaying {
                 display_winner(&mut game);
+                game.update_replay();
+                if game.analysis_mode {
+                    game.analyse_position();
+                }
+                game.update_review();
+
+                // Explore a variation from the current position
+                draw_rock_preview(&game);
+                if is_mouse_button_released(MouseButton::Left) {
+                    b_mouse_pressed = false;
+                } else if is_mouse_button_down(MouseButton::Left) && !b_mouse_pressed {
+                    b_mouse_pressed = true;
+                    let (mouse_x, mouse_y) = mouse_position();
+                    if mouse_x < (GRID_WINDOW_SIZE - 2) as f32
+                        && mouse_y < (GRID_WINDOW_SIZE - 2) as f32
+                        && !root_ui().is_mouse_over(Vec2::new(mouse_x, mouse_y))
+                        && !(game.show_review && mouse_y >= REVIEW_GRAPH_TOP)
+                    {
+                        game.play_variation(coord!(
+                            mouse_x as i16 / SQUARE_SIZE,
+                            mouse_y as i16 / SQUARE_SIZE
+                        ));
+                    }
+                }
+                if is_key_pressed(KeyCode::Left) {
+                    game.jump_to_move(game.rock_move.len().saturating_sub(1));
+                } else if is_key_pressed(KeyCode::Right) {
+                    game.jump_to_move(game.rock_move.len() + 1);
+                } else if is_key_pressed(KeyCode::Home) {
+                    game.jump_to_start();
+                } else if is_key_pressed(KeyCode::End) {
+                    game.jump_to_end();
+                }
+            } else if game.spectator.is_some() {
+                // The moves of a watched game are played by the broadcasting instance
             } else {
+                game.update_clock();
                 let opening = game.opening();
                 // Handle openings
                 if opening != Opening::None && !game.completed_opening {
@@ -119,8 +200,9 @@ async fn main() {
                             let (mouse_x, mouse_y) = mouse_position();
                             if mouse_x < (GRID_WINDOW_SIZE - 2) as f32
                                 && mouse_y < (GRID_WINDOW_SIZE - 2) as f32
+                                && !root_ui().is_mouse_over(Vec2::new(mouse_x, mouse_y))
                             {
-                                game.play_opening(coord!(
+                                game.play_local_move(coord!(
                                     mouse_x as i16 / SQUARE_SIZE,
                                     mouse_y as i16 / SQUARE_SIZE
                                 ));
@@ -134,13 +216,19 @@ async fn main() {
                 else if game.mode != GameMode::AvA {
                     // Computer Play
                     if game.mode == GameMode::PvA && game.current_player == game.computer_play_as {
-                        game.play_computer()
+                        game.play_computer_in_background()
                     }
                     // Move preview and await input
                     else {
                         if game.generate_recommended_move && !game.computer_generated_moves {
                             game.generate_computer_recommended_moves();
                         }
+                        if game.analysis_mode && game.mode == GameMode::PvP {
+                            game.analyse_position();
+                        }
+                        if game.mode == GameMode::PvA {
+                            game.ponder();
+                        }
                         draw_rock_preview(&game);
 
                         // Player play
@@ -156,8 +244,9 @@ async fn main() {
                             let (mouse_x, mouse_y) = mouse_position();
                             if mouse_x < (GRID_WINDOW_SIZE - 2) as f32
                                 && mouse_y < (GRID_WINDOW_SIZE - 2) as f32
+                                && !root_ui().is_mouse_over(Vec2::new(mouse_x, mouse_y))
                             {
-                                game.play_player(coord!(
+                                game.play_local_move(coord!(
                                     mouse_x as i16 / SQUARE_SIZE,
                                     mouse_y as i16 / SQUARE_SIZE
                                 ));
@@ -167,20 +256,148 @@ async fn main() {
                 }
                 // AvA just play in turn, no input
                 else {
-                    game.play_computer()
+                    game.play_computer_in_background()
                 }
             }
+            if game.computer_thinking.is_some() {
+                draw_computer_thinking(&mut game);
+            }
+            if game.network.is_some() {
+                draw_network(&mut game);
+            }
+            if game.spectator.is_some() {
+                draw_spectator(&game);
+            }
         }
 
         next_frame().await
     }
 }
 
-#[cfg(feature = "cli_ava")]
+#[cfg(all(feature = "cli_ava", not(feature = "cli_puzzles")))]
 fn main() {
+    use crate::{clock::TimeControl, computer::Algorithm, player::Player};
+
     let mut game = Game::default();
+    // The options are given as named arguments, e.g. `--algorithm pvs --time fischer:60+1`
+    let mut broadcast_address = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let Some(value) = args.next() else {
+            eprintln!("Missing value for {}", flag);
+            return;
+        };
+        match flag.as_str() {
+            // e.g. `mcts` or `pvs`
+            "--algorithm" => match value.parse::<Algorithm>() {
+                Ok(algorithm) => {
+                    game.algorithm_index = Algorithm::ALL
+                        .iter()
+                        .position(|candidate| *candidate == algorithm)
+                }
+                Err(error) => {
+                    eprintln!("{}", error);
+                    return;
+                }
+            },
+            // e.g. `fischer:60+1` or `byoyomi:60+3x5`
+            "--time" => match value.parse::<TimeControl>() {
+                Ok(time_control) => game.custom_time_control = Some(time_control),
+                Err(error) => {
+                    eprintln!("{}", error);
+                    return;
+                }
+            },
+            // e.g. `127.0.0.1:4343` or `file:match.txt`
+            "--broadcast" => broadcast_address = Some(value),
+            // JSON lines file of the searches of the computer
+            "--engine-log" => {
+                game.engine_log_path = value;
+                game.engine_log = true;
+            }
+            _ => {
+                eprintln!(
+                    "Unknown option {}, expected --algorithm, --time, --broadcast or --engine-log",
+                    flag
+                );
+                return;
+            }
+        }
+    }
+    if let Some(address) = broadcast_address {
+        game.broadcast_address = address;
+        game.toggle_broadcast();
+        if let Some(error) = game
+            .broadcast
+            .as_ref()
+            .and_then(|broadcast| broadcast.error.clone())
+        {
+            eprintln!("{}", error);
+            return;
+        }
+    }
     game.start(GameMode::AvA);
     while game.winner == Winner::None {
-        game.play_computer()
+        game.update_broadcast();
+        game.play_computer();
+        if game.clock.is_enabled() {
+            println!(
+                "clock: black {} white {}",
+                game.clock.time_text(Player::Black),
+                game.clock.time_text(Player::White)
+            );
+        }
+    }
+}
+
+#[cfg(feature = "cli_puzzles")]
+fn main() {
+    use crate::{
+        computer::{Algorithm, MoveOrdering, SearchOptions},
+        constants::DEPTH,
+        puzzle::{SuiteReport, PUZZLES},
+    };
+
+    // Compare each move ordering and then the candidates pruning options
+    let mut presets: Vec<SearchOptions> = MoveOrdering::ALL
+        .iter()
+        .map(|ordering| SearchOptions {
+            ordering: *ordering,
+            ..Default::default()
+        })
+        .collect();
+    presets.extend([
+        SearchOptions {
+            radius: 2,
+            ..Default::default()
+        },
+        SearchOptions {
+            threats_only: true,
+            ..Default::default()
+        },
+        SearchOptions {
+            beam_width: 10,
+            ..Default::default()
+        },
+        SearchOptions {
+            threats_only: true,
+            beam_width: 10,
+            ..Default::default()
+        },
+    ]);
+
+    for algorithm in Algorithm::ALL {
+        // The Greedy algorithm only search the first move
+        // -- and the Monte Carlo search doesn't use the move ordering
+        let presets = if let Algorithm::Greedy | Algorithm::MonteCarlo = algorithm {
+            &presets[..1]
+        } else {
+            &presets[..]
+        };
+        for depth in 1..=DEPTH {
+            for options in presets {
+                print!("{}", SuiteReport::run(&PUZZLES, algorithm, depth, *options));
+            }
+        }
     }
 }
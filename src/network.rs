@@ -0,0 +1,328 @@
+use crate::{board::Coordinates, player::Player, rules::RuleSet};
+use std::{
+    fmt,
+    io::{BufRead, BufReader, ErrorKind, Write},
+    net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs},
+    str::FromStr,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+// Both instances must speak the same protocol
+pub const PROTOCOL_VERSION: u32 = 1;
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:4242";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpeningChoice {
+    Black,
+    White,
+    PlaceStones,
+}
+
+// One message by line, e.g. `move 9x9` or `chat good game`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    // Sent by the host once connected, the opponent plays with these rules
+    Hello {
+        version: u32,
+        rules: RuleSet,
+        opening: usize,
+        time_control: usize,
+    },
+    Move(Coordinates),
+    Choice(OpeningChoice),
+    // Take back the last move, which was played by the player asking
+    UndoRequest,
+    UndoAccept,
+    UndoDecline,
+    Resign,
+    DrawOffer,
+    DrawAccept,
+    DrawDecline,
+    Chat(String),
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Message::Hello {
+                version,
+                rules,
+                opening,
+                time_control,
+            } => write!(
+                f,
+                "hello {} {} {} {} {} {} {}",
+                version,
+                rules.capture,
+                rules.game_ending_capture,
+                rules.no_double_three,
+                rules
+                    .move_limit
+                    .map_or("-".to_string(), |limit| limit.to_string()),
+                opening,
+                time_control
+            ),
+            Message::Move(coordinates) => write!(f, "move {}", coordinates),
+            Message::Choice(choice) => write!(
+                f,
+                "choice {}",
+                match choice {
+                    OpeningChoice::Black => "black",
+                    OpeningChoice::White => "white",
+                    OpeningChoice::PlaceStones => "place",
+                }
+            ),
+            Message::UndoRequest => write!(f, "undo"),
+            Message::UndoAccept => write!(f, "undo accept"),
+            Message::UndoDecline => write!(f, "undo decline"),
+            Message::Resign => write!(f, "resign"),
+            Message::DrawOffer => write!(f, "draw"),
+            Message::DrawAccept => write!(f, "draw accept"),
+            Message::DrawDecline => write!(f, "draw decline"),
+            // Chat messages stay on their line
+            Message::Chat(text) => write!(f, "chat {}", text.replace(['\n', '\r'], " ")),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid message {}", line);
+        let (name, arguments) = line.split_once(' ').unwrap_or((line, ""));
+        match (name, arguments) {
+            ("hello", arguments) => match arguments.split(' ').collect::<Vec<_>>()[..] {
+                [version, capture, game_ending_capture, no_double_three, move_limit, opening, time_control] => {
+                    Ok(Message::Hello {
+                        version: version.parse().map_err(|_| error())?,
+                        rules: RuleSet {
+                            capture: capture.parse().map_err(|_| error())?,
+                            game_ending_capture: game_ending_capture
+                                .parse()
+                                .map_err(|_| error())?,
+                            no_double_three: no_double_three.parse().map_err(|_| error())?,
+                            move_limit: match move_limit {
+                                "-" => None,
+                                limit => Some(limit.parse().map_err(|_| error())?),
+                            },
+                        },
+                        opening: opening.parse().map_err(|_| error())?,
+                        time_control: time_control.parse().map_err(|_| error())?,
+                    })
+                }
+                _ => Err(error()),
+            },
+            ("move", coordinates) => Ok(Message::Move(coordinates.parse()?)),
+            ("choice", "black") => Ok(Message::Choice(OpeningChoice::Black)),
+            ("choice", "white") => Ok(Message::Choice(OpeningChoice::White)),
+            ("choice", "place") => Ok(Message::Choice(OpeningChoice::PlaceStones)),
+            ("undo", "") => Ok(Message::UndoRequest),
+            ("undo", "accept") => Ok(Message::UndoAccept),
+            ("undo", "decline") => Ok(Message::UndoDecline),
+            ("resign", "") => Ok(Message::Resign),
+            ("draw", "") => Ok(Message::DrawOffer),
+            ("draw", "accept") => Ok(Message::DrawAccept),
+            ("draw", "decline") => Ok(Message::DrawDecline),
+            ("chat", text) => Ok(Message::Chat(text.to_string())),
+            _ => Err(error()),
+        }
+    }
+}
+
+// TCP stream with the opponent, the messages are read on another thread to never block the game
+pub struct Connection {
+    stream: TcpStream,
+    messages: Receiver<Result<Message, String>>,
+}
+
+impl Connection {
+    pub fn new(stream: TcpStream) -> Result<Connection, String> {
+        let reader = stream.try_clone().map_err(|error| error.to_string())?;
+        let _ = stream.set_nodelay(true);
+        let (sender, messages) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let message = match line {
+                    Ok(line) => line.trim_end().parse(),
+                    Err(error) => Err(error.to_string()),
+                };
+                let failed = message.is_err();
+                if sender.send(message).is_err() || failed {
+                    return;
+                }
+            }
+            let _ = sender.send(Err("Connection closed by the opponent".to_string()));
+        });
+        Ok(Connection { stream, messages })
+    }
+
+    pub fn connect(address: &str) -> Result<Connection, String> {
+        let address = address
+            .to_socket_addrs()
+            .map_err(|error| error.to_string())?
+            .next()
+            .ok_or_else(|| format!("Unknown address {}", address))?;
+        let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+            .map_err(|error| error.to_string())?;
+        Connection::new(stream)
+    }
+
+    pub fn send(&mut self, message: &Message) -> Result<(), String> {
+        writeln!(self.stream, "{}", message).map_err(|error| error.to_string())
+    }
+
+    // All the messages received since the last call, with the error that closed the connection
+    pub fn receive(&self) -> (Vec<Message>, Option<String>) {
+        let mut messages = vec![];
+        loop {
+            match self.messages.try_recv() {
+                Ok(Ok(message)) => messages.push(message),
+                Ok(Err(error)) => return (messages, Some(error)),
+                Err(TryRecvError::Empty) => return (messages, None),
+                Err(TryRecvError::Disconnected) => {
+                    return (messages, Some("Connection lost".to_string()))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+// State of a game played against another instance
+pub struct NetworkGame {
+    // The host waits for the opponent on this listener
+    pub listener: Option<TcpListener>,
+    pub connection: Option<Connection>,
+    pub is_host: bool,
+    // Player of this instance once the opening is completed
+    pub local_player: Player,
+    // Chat messages and events of the game
+    pub log: Vec<String>,
+    pub chat_input: String,
+    pub undo_requested: bool,
+    pub draw_offered: bool,
+    // This instance asked to take back its move or offered a draw, until the opponent answers
+    pub undo_pending: bool,
+    pub draw_pending: bool,
+    pub error: Option<String>,
+}
+
+impl NetworkGame {
+    fn new(is_host: bool) -> NetworkGame {
+        NetworkGame {
+            listener: None,
+            connection: None,
+            is_host,
+            // The host plays black without opening
+            local_player: if is_host {
+                Player::Black
+            } else {
+                Player::White
+            },
+            log: vec![],
+            chat_input: String::new(),
+            undo_requested: false,
+            draw_offered: false,
+            undo_pending: false,
+            draw_pending: false,
+            error: None,
+        }
+    }
+
+    // Listen for the opponent, a failure is kept as the error of the game
+    pub fn host(address: &str) -> NetworkGame {
+        let mut network = NetworkGame::new(true);
+        let listener = TcpListener::bind(address).and_then(|listener| {
+            listener.set_nonblocking(true)?;
+            Ok(listener)
+        });
+        match listener {
+            Ok(listener) => network.listener = Some(listener),
+            Err(error) => network.disconnect(error.to_string()),
+        }
+        network
+    }
+
+    pub fn join(address: &str) -> NetworkGame {
+        let mut network = NetworkGame::new(false);
+        match Connection::connect(address) {
+            Ok(connection) => network.connection = Some(connection),
+            Err(error) => network.disconnect(error),
+        }
+        network
+    }
+
+    // Address the host is listening on, with the port chosen by the system if it was 0
+    pub fn local_address(&self) -> Option<String> {
+        let listener = self.listener.as_ref()?;
+        listener
+            .local_addr()
+            .ok()
+            .map(|address| address.to_string())
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    // Check if the opponent joined the host, returns true once when it's connected
+    pub fn accept(&mut self) -> bool {
+        let Some(listener) = &self.listener else {
+            return false;
+        };
+        match listener.accept() {
+            Ok((stream, _)) => {
+                self.listener = None;
+                let _ = stream.set_nonblocking(false);
+                match Connection::new(stream) {
+                    Ok(connection) => {
+                        self.connection = Some(connection);
+                        true
+                    }
+                    Err(error) => {
+                        self.disconnect(error);
+                        false
+                    }
+                }
+            }
+            Err(error) if error.kind() == ErrorKind::WouldBlock => false,
+            Err(error) => {
+                self.disconnect(error.to_string());
+                false
+            }
+        }
+    }
+
+    pub fn send(&mut self, message: Message) {
+        if let Some(connection) = &mut self.connection {
+            if let Err(error) = connection.send(&message) {
+                self.disconnect(error);
+            }
+        }
+    }
+
+    // The messages must be applied before closing the connection on the error
+    pub fn receive(&self) -> (Vec<Message>, Option<String>) {
+        match &self.connection {
+            Some(connection) => connection.receive(),
+            None => (vec![], None),
+        }
+    }
+
+    // Close the connection and keep the reason to display it
+    pub fn disconnect(&mut self, error: String) {
+        self.listener = None;
+        self.connection = None;
+        self.log.push(error.clone());
+        self.error = Some(error);
+    }
+}
@@ -169,7 +169,7 @@ pub enum Category {
     // CloseTwo,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct PatternCount {
     pub five_in_row: u8,
     pub kill_four: u8,
@@ -230,6 +230,40 @@ impl PatternCount {
         }
     }
 
+    // Name and count of each pattern, and the captures of both players after the move
+    pub fn categories_mut(&mut self) -> [(&'static str, &mut u8); 18] {
+        [
+            ("five_in_row", &mut self.five_in_row),
+            ("kill_four", &mut self.kill_four),
+            ("open_four", &mut self.open_four),
+            ("reduce_three", &mut self.reduce_three),
+            ("close_four", &mut self.close_four),
+            ("open_three", &mut self.open_three),
+            ("kill_three", &mut self.kill_three),
+            ("blocked_capture", &mut self.blocked_capture),
+            ("captured_five_in_row", &mut self.captured_five_in_row),
+            ("close_three", &mut self.close_three),
+            ("open_two", &mut self.open_two),
+            ("reduce_two", &mut self.reduce_two),
+            ("close_two", &mut self.close_two),
+            ("created_captures", &mut self.created_captures),
+            ("reveal_capture", &mut self.reveal_capture),
+            ("total_captures", &mut self.total_captures),
+            ("opponent_captures", &mut self.opponent_captures),
+            ("inc_captures", &mut self.inc_captures),
+        ]
+    }
+
+    // Patterns created by the move, for the engine log and the search tree
+    pub fn categories(&self) -> Vec<(&'static str, u8)> {
+        self.clone()
+            .categories_mut()
+            .into_iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(name, count)| (name, *count))
+            .collect()
+    }
+
     pub fn from_patterns(patterns: &Vec<Category>) -> Self {
         let mut pattern_count = PatternCount::default();
         for &pattern in patterns {
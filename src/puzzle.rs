@@ -0,0 +1,328 @@
+use crate::{
+    board::{Board, Coordinates, Move},
+    computer::{Algorithm, Computer, SearchOptions},
+    constants::BOARD_SIZE,
+    macros::coord,
+    player::Player,
+    rules::RuleSet,
+};
+use colored::Colorize;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+// A puzzle is a position with a side to move and a set of moves that solve it
+// The suite format is a plain text file with one `key: value` per line
+// -- and puzzles separated by an empty line, lines starting with # are ignored
+//  name: Block the open four
+//  to_move: black
+//  rules: capture game_ending_capture no_double_three
+//  black: 9x9 10x10
+//  white: 5x9 6x9 7x9 8x9
+//  black_captures: 0
+//  white_captures: 0
+//  best: 4x9 9x9
+//  avoid: 12x12
+// Coordinates use the same `XxY` notation as the Move display
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub name: String,
+    pub to_move: Player,
+    pub rules: RuleSet,
+    pub black: Vec<Coordinates>,
+    pub white: Vec<Coordinates>,
+    pub black_captures: u8,
+    pub white_captures: u8,
+    pub best: Vec<Coordinates>,
+    pub avoid: Vec<Coordinates>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PuzzleResult {
+    pub name: String,
+    pub algorithm: Algorithm,
+    pub depth: usize,
+    pub options: SearchOptions,
+    pub solved: bool,
+    pub played: Option<Coordinates>,
+    pub duration: Duration,
+    pub nodes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SuiteReport {
+    pub algorithm: Algorithm,
+    pub depth: usize,
+    pub options: SearchOptions,
+    pub results: Vec<PuzzleResult>,
+}
+
+lazy_static! {
+    // Default suite shipped with the repository
+    pub static ref PUZZLES: Vec<Puzzle> =
+        Puzzle::parse_suite(include_str!("../puzzles/suite.txt")).unwrap();
+}
+
+#[allow(clippy::manual_range_contains)]
+pub fn parse_coordinates(value: &str) -> Result<Coordinates, String> {
+    let (x, y) = value
+        .split_once('x')
+        .ok_or(format!("Invalid coordinates `{}`", value))?;
+    let x = x
+        .parse::<i16>()
+        .map_err(|_| format!("Invalid x coordinate in `{}`", value))?;
+    let y = y
+        .parse::<i16>()
+        .map_err(|_| format!("Invalid y coordinate in `{}`", value))?;
+    if x < 0 || x >= BOARD_SIZE || y < 0 || y >= BOARD_SIZE {
+        return Err(format!("Coordinates `{}` are out of the board", value));
+    }
+    Ok(coord!(x, y))
+}
+
+fn parse_coordinates_list(value: &str) -> Result<Vec<Coordinates>, String> {
+    value.split_whitespace().map(parse_coordinates).collect()
+}
+
+fn parse_player(value: &str) -> Result<Player, String> {
+    match value {
+        "black" => Ok(Player::Black),
+        "white" => Ok(Player::White),
+        _ => Err(format!("Invalid player `{}`", value)),
+    }
+}
+
+fn parse_rules(value: &str) -> Result<RuleSet, String> {
+    let mut rules = RuleSet {
+        capture: false,
+        game_ending_capture: false,
+        no_double_three: false,
+        move_limit: None,
+    };
+    for rule in value.split_whitespace() {
+        match rule {
+            "capture" => rules.capture = true,
+            "game_ending_capture" => rules.game_ending_capture = true,
+            "no_double_three" => rules.no_double_three = true,
+            "none" => {}
+            _ => return Err(format!("Unknown rule `{}`", rule)),
+        }
+    }
+    Ok(rules)
+}
+
+impl Puzzle {
+    pub fn parse(source: &str) -> Result<Puzzle, String> {
+        let mut name: Option<String> = None;
+        let mut to_move: Option<Player> = None;
+        let mut puzzle = Puzzle {
+            name: String::new(),
+            to_move: Player::Black,
+            rules: RuleSet::default(),
+            black: vec![],
+            white: vec![],
+            black_captures: 0,
+            white_captures: 0,
+            best: vec![],
+            avoid: vec![],
+        };
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or(format!("Invalid line `{}`", line))?;
+            let value = value.trim();
+            match key.trim() {
+                "name" => name = Some(value.to_string()),
+                "to_move" => to_move = Some(parse_player(value)?),
+                "rules" => puzzle.rules = parse_rules(value)?,
+                "black" => puzzle.black = parse_coordinates_list(value)?,
+                "white" => puzzle.white = parse_coordinates_list(value)?,
+                "black_captures" => {
+                    puzzle.black_captures = value
+                        .parse()
+                        .map_err(|_| format!("Invalid capture count `{}`", value))?
+                }
+                "white_captures" => {
+                    puzzle.white_captures = value
+                        .parse()
+                        .map_err(|_| format!("Invalid capture count `{}`", value))?
+                }
+                "best" => puzzle.best = parse_coordinates_list(value)?,
+                "avoid" => puzzle.avoid = parse_coordinates_list(value)?,
+                key => return Err(format!("Unknown key `{}`", key)),
+            }
+        }
+        puzzle.name = name.ok_or("Missing puzzle name")?;
+        puzzle.to_move = to_move.ok_or(format!("Missing side to move in `{}`", puzzle.name))?;
+        if let Some(coordinates) = puzzle
+            .black
+            .iter()
+            .find(|coordinates| puzzle.white.contains(coordinates))
+        {
+            return Err(format!(
+                "Puzzle `{}` has a black and a white rock on {}",
+                puzzle.name, coordinates
+            ));
+        }
+        if puzzle.best.is_empty() && puzzle.avoid.is_empty() {
+            return Err(format!(
+                "Puzzle `{}` has no best or avoid moves",
+                puzzle.name
+            ));
+        }
+        Ok(puzzle)
+    }
+
+    // Puzzles are separated by one or more empty lines
+    pub fn parse_suite(source: &str) -> Result<Vec<Puzzle>, String> {
+        let mut puzzles: Vec<Puzzle> = vec![];
+        let mut block: Vec<&str> = vec![];
+        for line in source.lines().chain(std::iter::once("")) {
+            if line.trim().is_empty() {
+                // Skip blocks that only contains comments
+                if block
+                    .iter()
+                    .any(|line| !line.trim().is_empty() && !line.trim().starts_with('#'))
+                {
+                    puzzles.push(Puzzle::parse(&block.join("\n"))?);
+                }
+                block.clear();
+            } else {
+                block.push(line);
+            }
+        }
+        Ok(puzzles)
+    }
+
+    // Build the Board of the puzzle, rocks are placed without checking the rules
+    pub fn board(&self) -> Board {
+        let mut board = Board::default();
+        let rules = RuleSet {
+            capture: false,
+            ..self.rules
+        };
+        for (rocks, player) in [(&self.black, Player::Black), (&self.white, Player::White)] {
+            for coordinates in rocks {
+                board.set_move(
+                    &rules,
+                    &Move {
+                        player,
+                        coordinates: *coordinates,
+                    },
+                );
+            }
+        }
+        board.black.captures = self.black_captures;
+        board.white.captures = self.white_captures;
+        board
+    }
+
+    // A move solves the puzzle if it's one of the best moves (if there is any)
+    // -- and it's not one of the moves to avoid
+    pub fn is_solved_by(&self, coordinates: &Coordinates) -> bool {
+        (self.best.is_empty() || self.best.contains(coordinates))
+            && !self.avoid.contains(coordinates)
+    }
+
+    pub fn run(&self, algorithm: Algorithm, depth: usize, options: SearchOptions) -> PuzzleResult {
+        let mut board = self.board();
+        let mut computer = Computer {
+            options,
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let play_result = computer.play(algorithm, &self.rules, &mut board, depth, self.to_move);
+        let duration = start.elapsed();
+        let played = play_result
+            .ok()
+            .and_then(|evaluation| evaluation.movements.first().map(|m| m.coordinates));
+        PuzzleResult {
+            name: self.name.clone(),
+            algorithm,
+            depth,
+            options,
+            solved: played
+                .map(|coordinates| self.is_solved_by(&coordinates))
+                .unwrap_or(false),
+            played,
+            duration,
+            nodes: computer.statistics.nodes,
+        }
+    }
+}
+
+impl SuiteReport {
+    pub fn run(
+        puzzles: &[Puzzle],
+        algorithm: Algorithm,
+        depth: usize,
+        options: SearchOptions,
+    ) -> SuiteReport {
+        SuiteReport {
+            results: puzzles
+                .iter()
+                .map(|puzzle| puzzle.run(algorithm, depth, options))
+                .collect(),
+            algorithm,
+            depth,
+            options,
+        }
+    }
+
+    pub fn solved(&self) -> usize {
+        self.results.iter().filter(|result| result.solved).count()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.results.iter().map(|result| result.duration).sum()
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.results.iter().map(|result| result.nodes).sum()
+    }
+}
+
+impl fmt::Display for PuzzleResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} played {} in {}ms ({} nodes)",
+            if self.solved {
+                "solved".green()
+            } else {
+                "failed".red()
+            },
+            self.name,
+            self.played
+                .map(|coordinates| format!("{}x{}", coordinates.x, coordinates.y))
+                .unwrap_or_else(|| "nothing".to_string()),
+            self.duration.as_millis(),
+            self.nodes
+        )
+    }
+}
+
+impl fmt::Display for SuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:?} depth {} {}: {}/{} solved in {}ms ({} nodes)",
+            self.algorithm,
+            self.depth,
+            self.options,
+            self.solved(),
+            self.results.len(),
+            self.duration().as_millis(),
+            self.nodes()
+        )?;
+        for result in &self.results {
+            writeln!(f, "  {}", result)?;
+        }
+        Ok(())
+    }
+}
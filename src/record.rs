@@ -0,0 +1,166 @@
+use crate::{
+    board::{Coordinates, Move},
+    player::Player,
+    rules::RuleSet,
+    variation::VariationTree,
+};
+use std::{collections::BTreeMap, fmt, fs, path::Path, str::FromStr};
+
+// Saved state of a game, written as one `key: value` by line
+// -- the settings are the state of the interface, and the variations all the moves
+#[derive(Debug, Clone, Default)]
+pub struct GameRecord {
+    pub rules: RuleSet,
+    pub settings: BTreeMap<String, String>,
+    pub variations: VariationTree,
+}
+
+pub fn parse_move(text: &str) -> Result<Move, String> {
+    let error = || format!("Invalid move {}", text);
+    let (player, coordinates) = text.split_once(' ').ok_or_else(error)?;
+    let coordinates: Coordinates = coordinates.parse().map_err(|_| error())?;
+    if !coordinates.is_on_board() {
+        return Err(error());
+    }
+    Ok(Move {
+        player: match player {
+            "B" => Player::Black,
+            "W" => Player::White,
+            _ => return Err(error()),
+        },
+        coordinates,
+    })
+}
+
+pub fn move_text(movement: &Move) -> String {
+    format!(
+        "{} {}",
+        if movement.player == Player::Black {
+            "B"
+        } else {
+            "W"
+        },
+        movement.coordinates
+    )
+}
+
+impl GameRecord {
+    pub fn set(&mut self, key: &str, value: impl ToString) {
+        self.settings.insert(key.to_string(), value.to_string());
+    }
+
+    pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.settings.get(key)?.parse().ok()
+    }
+
+    // Moves are written on one line, separated by commas
+    pub fn set_moves(&mut self, key: &str, moves: &[Move]) {
+        let moves: Vec<String> = moves.iter().map(move_text).collect();
+        self.set(key, moves.join(", "));
+    }
+
+    pub fn get_moves(&self, key: &str) -> Option<Vec<Move>> {
+        let moves = self.settings.get(key)?;
+        moves
+            .split(", ")
+            .filter(|movement| !movement.is_empty())
+            .map(parse_move)
+            .collect::<Result<_, _>>()
+            .ok()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        fs::write(path, self.to_string()).map_err(|error| error.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<GameRecord, String> {
+        fs::read_to_string(path)
+            .map_err(|error| error.to_string())?
+            .parse()
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "capture: {}", self.rules.capture)?;
+        writeln!(f, "game_ending_capture: {}", self.rules.game_ending_capture)?;
+        writeln!(f, "no_double_three: {}", self.rules.no_double_three)?;
+        if let Some(move_limit) = self.rules.move_limit {
+            writeln!(f, "move_limit: {}", move_limit)?;
+        }
+        for (key, value) in &self.settings {
+            writeln!(f, "{}: {}", key, value)?;
+        }
+        // Each variation references its parent by its position in the list
+        let order = self.variations.preorder();
+        let position = |node: usize| order.iter().position(|other| *other == node);
+        for node in &order {
+            let variation = &self.variations.nodes[*node];
+            writeln!(
+                f,
+                "variation: {} {}",
+                variation
+                    .parent
+                    .and_then(position)
+                    .map_or("-".to_string(), |parent| parent.to_string()),
+                move_text(&variation.movement)
+            )?;
+        }
+        if let Some(current) = self.variations.current.and_then(position) {
+            writeln!(f, "current: {}", current)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut record = GameRecord::default();
+        let mut current = None;
+        let error = |line: &str| format!("Invalid line {}", line);
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, value) = line.split_once(": ").ok_or_else(|| error(line))?;
+            match key {
+                "capture" => record.rules.capture = value.parse().map_err(|_| error(line))?,
+                "game_ending_capture" => {
+                    record.rules.game_ending_capture = value.parse().map_err(|_| error(line))?
+                }
+                "no_double_three" => {
+                    record.rules.no_double_three = value.parse().map_err(|_| error(line))?
+                }
+                "move_limit" => {
+                    record.rules.move_limit = Some(value.parse().map_err(|_| error(line))?)
+                }
+                "variation" => {
+                    let (parent, movement) = value.split_once(' ').ok_or_else(|| error(line))?;
+                    let parent = match parent {
+                        "-" => None,
+                        parent => {
+                            let parent: usize = parent.parse().map_err(|_| error(line))?;
+                            if parent >= record.variations.nodes.len() {
+                                return Err(error(line));
+                            }
+                            Some(parent)
+                        }
+                    };
+                    // The variations are added in the order of the file, a duplicate would shift the indexes
+                    let length = record.variations.nodes.len();
+                    if record.variations.add(parent, parse_move(movement)?) != length {
+                        return Err(error(line));
+                    }
+                }
+                "current" => current = Some(value.parse::<usize>().map_err(|_| error(line))?),
+                _ => record.set(key, value),
+            }
+        }
+        if let Some(current) = current {
+            if current >= record.variations.nodes.len() {
+                return Err(format!("Invalid current move {}", current));
+            }
+        }
+        record.variations.current = current;
+        Ok(record)
+    }
+}
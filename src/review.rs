@@ -0,0 +1,189 @@
+use crate::{
+    board::{Board, Move},
+    computer::{mate_in, Algorithm, Computer, SearchControl},
+    player::Player,
+    rules::RuleSet,
+};
+use std::{
+    fmt,
+    thread::{self, JoinHandle},
+};
+
+// Score lost by a movement compared to the best move of the engine
+// -- an open three is worth 30000, a simple two 500
+const INACCURACY_LOSS: i64 = 5000;
+const BLUNDER_LOSS: i64 = 30000;
+// Higher scores are drawn at the top or bottom of the evaluation graph
+const GRAPH_SCORE: i32 = 100000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveQuality {
+    Best,
+    Good,
+    Inaccuracy,
+    Blunder,
+    MissedWin,
+}
+
+impl MoveQuality {
+    // Short annotation added after the move in the move list
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            MoveQuality::Best => "!",
+            MoveQuality::Good => "",
+            MoveQuality::Inaccuracy => "?!",
+            MoveQuality::Blunder => "??",
+            MoveQuality::MissedWin => "?",
+        }
+    }
+
+    // Compare the scores of the played and the best movements, for the player of the movement
+    pub fn classify(score: i32, best_score: i32) -> MoveQuality {
+        let is_win = |score: i32| mate_in(score).is_some_and(|moves| moves > 0);
+        let is_loss = |score: i32| mate_in(score).is_some_and(|moves| moves < 0);
+        if is_win(best_score) && !is_win(score) {
+            return MoveQuality::MissedWin;
+        } else if score >= best_score {
+            return MoveQuality::Best;
+        } else if is_loss(score) && !is_loss(best_score) {
+            return MoveQuality::Blunder;
+        }
+        let loss = best_score as i64 - score as i64;
+        if loss < INACCURACY_LOSS {
+            MoveQuality::Good
+        } else if loss < BLUNDER_LOSS {
+            MoveQuality::Inaccuracy
+        } else {
+            MoveQuality::Blunder
+        }
+    }
+}
+
+impl fmt::Display for MoveQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                MoveQuality::Best => "best",
+                MoveQuality::Good => "good",
+                MoveQuality::Inaccuracy => "inaccuracy",
+                MoveQuality::Blunder => "blunder",
+                MoveQuality::MissedWin => "missed win",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MoveReview {
+    pub movement: Move,
+    pub quality: MoveQuality,
+    // Scores of the played and of the best movements, for the player of the movement
+    pub score: i32,
+    pub best_move: Move,
+    pub best_score: i32,
+}
+
+impl MoveReview {
+    // Score of the position after the movement for black, between -1 and 1 for the evaluation graph
+    pub fn black_advantage(&self) -> f32 {
+        let score = if mate_in(self.score).is_some() {
+            self.score.signum() * GRAPH_SCORE
+        } else {
+            self.score.clamp(-GRAPH_SCORE, GRAPH_SCORE)
+        };
+        let advantage = score as f32 / GRAPH_SCORE as f32;
+        if self.movement.player == Player::Black {
+            advantage
+        } else {
+            -advantage
+        }
+    }
+}
+
+impl fmt::Display for MoveReview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.quality)?;
+        if self.quality != MoveQuality::Best {
+            write!(f, ", best {}", self.best_move.coordinates)?;
+        }
+        Ok(())
+    }
+}
+
+// Replay the moves from an empty board and compare each one with the best move of the engine
+// -- both movements are scored by the same search of the reply to be comparable
+pub fn review_game(
+    computer: &mut Computer,
+    algorithm: Algorithm,
+    rules: &RuleSet,
+    moves: &[Move],
+    depth: usize,
+) -> Result<Vec<MoveReview>, String> {
+    let mut board = Board::default();
+    let mut reviews = vec![];
+    for movement in moves {
+        if computer.control.is_stopped() {
+            break;
+        }
+        let best = computer.play(algorithm, rules, &mut board, depth, movement.player)?;
+        let best_move = best.movements.first().copied().unwrap_or(*movement);
+        let score = computer
+            .evaluate_move(algorithm, rules, &mut board, depth, movement)?
+            .score;
+        let best_score = if best_move == *movement {
+            score
+        } else {
+            computer
+                .evaluate_move(algorithm, rules, &mut board, depth, &best_move)?
+                .score
+        };
+        reviews.push(MoveReview {
+            movement: *movement,
+            quality: MoveQuality::classify(score, best_score),
+            score,
+            best_move,
+            best_score,
+        });
+        board.set_move(rules, movement);
+    }
+    Ok(reviews)
+}
+
+// A review running on another thread
+pub struct BackgroundReview {
+    pub control: SearchControl,
+    handle: JoinHandle<Result<Vec<MoveReview>, String>>,
+}
+
+impl BackgroundReview {
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    // Stop the review, it will return the moves reviewed so far
+    pub fn cancel(&self) {
+        self.control.stop();
+    }
+
+    pub fn join(self) -> Result<Vec<MoveReview>, String> {
+        self.handle
+            .join()
+            .unwrap_or_else(|_| Err("Review thread panicked".to_string()))
+    }
+}
+
+pub fn review_in_background(
+    mut computer: Computer,
+    algorithm: Algorithm,
+    rules: RuleSet,
+    moves: Vec<Move>,
+    depth: usize,
+) -> BackgroundReview {
+    let control = SearchControl::default();
+    computer.control = control.clone();
+    let handle =
+        thread::spawn(move || review_game(&mut computer, algorithm, &rules, &moves, depth));
+    BackgroundReview { control, handle }
+}
@@ -1,8 +1,10 @@
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct RuleSet {
     pub capture: bool,
     pub game_ending_capture: bool,
     pub no_double_three: bool,
+    // The game is a draw when this number of moves is reached
+    pub move_limit: Option<u16>,
 }
 
 impl Default for RuleSet {
@@ -11,6 +13,7 @@ impl Default for RuleSet {
             capture: true,
             game_ending_capture: true,
             no_double_three: true,
+            move_limit: None,
         }
     }
 }
@@ -0,0 +1,381 @@
+use crate::{
+    board::{Coordinates, Move},
+    json::{move_json, player_name, Json},
+    patterns::PatternCount,
+    player::Player,
+};
+use std::io::{BufRead, Write};
+
+// JSON file of the last recorded search of the computer
+pub const SEARCH_TREE_FILE: &str = "gomoku_search_tree.json";
+// Nodes kept by default, a search at depth 4 visits a few hundred thousands
+pub const DEFAULT_NODE_LIMIT: usize = 20000;
+
+// Node visited by the negamax or minimax search, the scores are the ones of the algorithm:
+// -- from the player to move for negamax, and from the computer for minimax
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchNode {
+    // Movement that led to the node, None for the root
+    pub movement: Option<Move>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    pub ply: usize,
+    // Window the node was searched with
+    pub alpha: i32,
+    pub beta: i32,
+    // Score returned to the parent, None if the search was stopped inside the node
+    pub score: Option<i32>,
+    // Child that caused a cutoff, the next children were not searched
+    pub cutoff: Option<Coordinates>,
+    // Patterns created by the movement, they are the score of a leaf
+    pub patterns: Option<PatternCount>,
+}
+
+// Nodes of a search in the order they were visited, up to a limit
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchTree {
+    pub nodes: Vec<SearchNode>,
+    pub limit: usize,
+    // Some nodes were not recorded because of the limit
+    pub truncated: bool,
+    // Node being searched, the next nodes are its children
+    current: Option<usize>,
+}
+
+impl SearchTree {
+    pub fn new(limit: usize) -> SearchTree {
+        SearchTree {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = SearchTree::new(self.limit);
+    }
+
+    // Record a node as a child of the node being searched
+    // -- once the limit is reached the end of the search is not recorded
+    pub fn enter(
+        &mut self,
+        movement: Option<Move>,
+        patterns: Option<PatternCount>,
+        (alpha, beta): (i32, i32),
+    ) -> Option<usize> {
+        if self.truncated || self.nodes.len() >= self.limit {
+            self.truncated = true;
+            return None;
+        }
+        let index = self.nodes.len();
+        let parent = self.current;
+        self.nodes.push(SearchNode {
+            movement,
+            parent,
+            children: vec![],
+            ply: parent.map_or(0, |parent| self.nodes[parent].ply + 1),
+            alpha,
+            beta,
+            score: None,
+            cutoff: None,
+            patterns,
+        });
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(index);
+        }
+        self.current = Some(index);
+        Some(index)
+    }
+
+    pub fn exit(&mut self, node: Option<usize>, score: Option<i32>) {
+        if let Some(index) = node {
+            self.nodes[index].score = score;
+            self.current = self.nodes[index].parent;
+        }
+    }
+
+    // The movement caused a cutoff in the node being searched
+    pub fn cutoff(&mut self, movement: &Move) {
+        if let Some(index) = self.current {
+            self.nodes[index].cutoff = Some(movement.coordinates);
+        }
+    }
+
+    // Movements from the root to the node
+    pub fn path(&self, index: usize) -> Vec<Move> {
+        let mut path = vec![];
+        let mut node = Some(index);
+        while let Some(index) = node {
+            path.extend(self.nodes[index].movement);
+            node = self.nodes[index].parent;
+        }
+        path.reverse();
+        path
+    }
+
+    // Number of recorded nodes below the node, including itself
+    pub fn subtree_size(&self, index: usize) -> usize {
+        1 + self.nodes[index]
+            .children
+            .iter()
+            .map(|child| self.subtree_size(*child))
+            .sum::<usize>()
+    }
+
+    // The children are not saved, they are found again from the parents
+    pub fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("limit", (self.limit as i64).into()),
+            ("truncated", self.truncated.into()),
+            (
+                "nodes",
+                Json::Array(
+                    self.nodes
+                        .iter()
+                        .map(|node| {
+                            Json::object(vec![
+                                ("move", node.movement.as_ref().map_or(Json::Null, move_json)),
+                                ("parent", node.parent.map(|parent| parent as i64).into()),
+                                ("alpha", i64::from(node.alpha).into()),
+                                ("beta", i64::from(node.beta).into()),
+                                ("score", node.score.map(i64::from).into()),
+                                (
+                                    "cutoff",
+                                    node.cutoff
+                                        .map(|coordinates| coordinates.to_string())
+                                        .into(),
+                                ),
+                                (
+                                    "patterns",
+                                    node.patterns.as_ref().map_or(Json::Null, |patterns| {
+                                        Json::object(
+                                            patterns
+                                                .categories()
+                                                .into_iter()
+                                                .map(|(name, count)| {
+                                                    (name, i64::from(count).into())
+                                                })
+                                                .collect(),
+                                        )
+                                    }),
+                                ),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+
+    pub fn from_json(json: &Json) -> Result<SearchTree, String> {
+        let Some(Json::Array(nodes)) = json.get("nodes") else {
+            return Err("Missing nodes".to_string());
+        };
+        let mut tree = SearchTree {
+            limit: json
+                .get("limit")
+                .and_then(Json::as_i64)
+                .map_or(nodes.len(), |limit| limit as usize),
+            truncated: json
+                .get("truncated")
+                .and_then(Json::as_bool)
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        for (index, node) in nodes.iter().enumerate() {
+            let invalid = |field: &str| format!("Invalid {} of node {}", field, index);
+            let integer = |field: &str| -> Result<Option<i64>, String> {
+                match node.get(field) {
+                    None | Some(Json::Null) => Ok(None),
+                    Some(value) => value.as_i64().map(Some).ok_or_else(|| invalid(field)),
+                }
+            };
+            let parent = match integer("parent")? {
+                Some(parent) if parent >= 0 && (parent as usize) < index => Some(parent as usize),
+                Some(_) => return Err(invalid("parent")),
+                None if index > 0 => return Err(invalid("parent")),
+                None => None,
+            };
+            let movement = match node.get("move") {
+                None | Some(Json::Null) => None,
+                Some(movement) => Some(json_move(movement).ok_or_else(|| invalid("move"))?),
+            };
+            let cutoff = match node.get("cutoff") {
+                None | Some(Json::Null) => None,
+                Some(cutoff) => Some(
+                    cutoff
+                        .as_str()
+                        .and_then(|text| text.parse::<Coordinates>().ok())
+                        .ok_or_else(|| invalid("cutoff"))?,
+                ),
+            };
+            let patterns = match node.get("patterns") {
+                None | Some(Json::Null) => None,
+                Some(Json::Object(categories)) => {
+                    let mut patterns = PatternCount::default();
+                    for (name, count) in categories {
+                        let count = count
+                            .as_i64()
+                            .and_then(|count| u8::try_from(count).ok())
+                            .ok_or_else(|| invalid("patterns"))?;
+                        let (_, value) = patterns
+                            .categories_mut()
+                            .into_iter()
+                            .find(|(category, _)| category == name)
+                            .ok_or_else(|| invalid("patterns"))?;
+                        *value = count;
+                    }
+                    Some(patterns)
+                }
+                Some(_) => return Err(invalid("patterns")),
+            };
+            let window = |field: &str| -> Result<i32, String> {
+                integer(field)?
+                    .and_then(|value| i32::try_from(value).ok())
+                    .ok_or_else(|| invalid(field))
+            };
+            tree.nodes.push(SearchNode {
+                movement,
+                parent,
+                children: vec![],
+                ply: parent.map_or(0, |parent| tree.nodes[parent].ply + 1),
+                alpha: window("alpha")?,
+                beta: window("beta")?,
+                score: integer("score")?
+                    .map(|score| i32::try_from(score).map_err(|_| invalid("score")))
+                    .transpose()?,
+                cutoff,
+                patterns,
+            });
+            if let Some(parent) = parent {
+                tree.nodes[parent].children.push(index);
+            }
+        }
+        Ok(tree)
+    }
+}
+
+fn json_move(json: &Json) -> Option<Move> {
+    let player = match json.get("player").and_then(Json::as_str)? {
+        "black" => Player::Black,
+        "white" => Player::White,
+        _ => return None,
+    };
+    let coordinate = |field: &str| {
+        json.get(field)
+            .and_then(Json::as_i64)
+            .and_then(|value| i16::try_from(value).ok())
+    };
+    Some(Move {
+        player,
+        coordinates: Coordinates {
+            x: coordinate("x")?,
+            y: coordinate("y")?,
+        },
+    })
+}
+
+fn score_text(score: Option<i32>) -> String {
+    score.map_or("stopped".to_string(), |score| score.to_string())
+}
+
+fn write_node(tree: &SearchTree, index: usize, output: &mut impl Write) -> std::io::Result<()> {
+    let node = &tree.nodes[index];
+    let path = tree.path(index);
+    if path.is_empty() {
+        writeln!(output, "root")?;
+    } else {
+        let moves: Vec<String> = path.iter().map(ToString::to_string).collect();
+        writeln!(output, "{}", moves.join(" "))?;
+    }
+    writeln!(
+        output,
+        "ply {} window [{}, {}] score {}{}",
+        node.ply,
+        node.alpha,
+        node.beta,
+        score_text(node.score),
+        node.cutoff.map_or(String::new(), |coordinates| format!(
+            " cutoff by {}",
+            coordinates
+        ))
+    )?;
+    if let Some(patterns) = &node.patterns {
+        let categories: Vec<String> = patterns
+            .categories()
+            .iter()
+            .map(|(name, count)| format!("{} {}", name, count))
+            .collect();
+        writeln!(output, "patterns: {}", categories.join(", "))?;
+    }
+    for (position, child) in node.children.iter().enumerate() {
+        let child = &tree.nodes[*child];
+        let Some(movement) = child.movement else {
+            continue;
+        };
+        writeln!(
+            output,
+            "  {:>3}. {} {} score {} [{}, {}] {} nodes{}",
+            position + 1,
+            player_name(movement.player),
+            movement.coordinates,
+            score_text(child.score),
+            child.alpha,
+            child.beta,
+            tree.subtree_size(node.children[position]),
+            if child.cutoff.is_some() {
+                " cutoff"
+            } else {
+                ""
+            }
+        )?;
+    }
+    Ok(())
+}
+
+// Navigate the tree from the root with the commands read from the input:
+// -- the number of a child to enter it, `u` for the parent, `r` for the root and `q` to quit
+pub fn explore(
+    tree: &SearchTree,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> std::io::Result<()> {
+    if tree.nodes.is_empty() {
+        return writeln!(output, "the search tree is empty");
+    }
+    writeln!(
+        output,
+        "{} nodes{}",
+        tree.nodes.len(),
+        if tree.truncated {
+            format!(", truncated at {}", tree.limit)
+        } else {
+            String::new()
+        }
+    )?;
+    let mut current = 0;
+    write_node(tree, current, &mut output)?;
+    for line in input.lines() {
+        let line = line?;
+        let command = line.trim();
+        match command {
+            "q" => break,
+            "u" => current = tree.nodes[current].parent.unwrap_or(current),
+            "r" => current = 0,
+            "" => (),
+            _ => match command
+                .parse::<usize>()
+                .ok()
+                .and_then(|position| position.checked_sub(1))
+                .and_then(|position| tree.nodes[current].children.get(position))
+            {
+                Some(child) => current = *child,
+                None => {
+                    writeln!(output, "unknown command {}", command)?;
+                    continue;
+                }
+            },
+        }
+        write_node(tree, current, &mut output)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,553 @@
+use crate::{
+    board::{Coordinates, Move},
+    computer::Algorithm,
+    constants::{BOARD_SIZE, DEPTH},
+    game::{Game, GameMode, Winner},
+    json::{board_json, move_json, player_name, rules_json, Json},
+    player::Player,
+    rock::Rock,
+    rules::RuleSet,
+};
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_SERVER_ADDRESS: &str = "127.0.0.1:8080";
+// Deeper searches would block the server for minutes
+const MAX_DEPTH: usize = 10;
+// The requests are answered one by one, a search never blocks the server longer than its budget
+pub const DEFAULT_SEARCH_TIME: Duration = Duration::from_secs(3);
+pub const MAX_SEARCH_TIME: Duration = Duration::from_secs(60);
+const MAX_BODY_SIZE: usize = 64 * 1024;
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+const MAX_HEADERS: usize = 64;
+// The connections are answered one by one, a silent client only blocks the others for this long
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+impl Request {
+    pub fn new(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    // An empty body is an empty object
+    fn json(&self) -> Result<Json, Response> {
+        if self.body.trim().is_empty() {
+            return Ok(Json::Object(vec![]));
+        }
+        match self.body.parse::<Json>() {
+            Ok(json @ Json::Object(_)) => Ok(json),
+            Ok(_) => Err(Response::error(400, "The body must be a JSON object")),
+            Err(error) => Err(Response::error(400, &format!("Invalid JSON: {}", error))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Option<Json>,
+}
+
+impl Response {
+    fn json(status: u16, body: Json) -> Response {
+        Response {
+            status,
+            body: Some(body),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response::json(status, Json::object(vec![("error", message.into())]))
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            201 => "Created",
+            204 => "No Content",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            422 => "Unprocessable Entity",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn write(&self, stream: &mut impl Write) -> std::io::Result<()> {
+        let body = self.body.as_ref().map_or(String::new(), Json::to_string);
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+}
+
+// Read a line up to its end, refused if it's longer than MAX_LINE_LENGTH
+fn read_line(reader: &mut impl BufRead) -> Result<String, String> {
+    let mut line = String::new();
+    reader
+        .take(MAX_LINE_LENGTH as u64 + 1)
+        .read_line(&mut line)
+        .map_err(|error| error.to_string())?;
+    if line.len() > MAX_LINE_LENGTH {
+        return Err(format!("Line longer than {} bytes", MAX_LINE_LENGTH));
+    }
+    Ok(line)
+}
+
+// Read a request line, the headers and the body of its Content-Length
+pub fn read_request(stream: impl Read) -> Result<Request, String> {
+    let mut reader = BufReader::new(stream);
+    let line = read_line(&mut reader)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(format!("Invalid request line {}", line.trim_end()));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+    let mut content_length = 0;
+    let mut headers = 0;
+    loop {
+        let line = read_line(&mut reader)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            return Err(format!("More than {} headers", MAX_HEADERS));
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid Content-Length {}", value.trim()))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        return Err(format!("Body of {} bytes is too large", content_length));
+    }
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|error| error.to_string())?;
+    Ok(Request {
+        method,
+        path,
+        body: String::from_utf8(body).map_err(|error| error.to_string())?,
+    })
+}
+
+// Everything a client needs to display the game, the board is a line of . B W by row
+fn game_json(id: usize, game: &Game) -> Json {
+    let (winner, reason) = match &game.winner {
+        Winner::None => (None, None),
+        Winner::Black(reason) => (Some("black"), Some(reason.to_string())),
+        Winner::White(reason) => (Some("white"), Some(reason.to_string())),
+        Winner::Draw(reason) => (Some("draw"), Some(reason.to_string())),
+    };
+    Json::object(vec![
+        ("id", (id as i64).into()),
+        ("rules", rules_json(&game.rules)),
+        ("current_player", player_name(game.current_player).into()),
+        ("winner", winner.into()),
+        ("reason", reason.into()),
+        (
+            "captures",
+            Json::object(vec![
+                ("black", i64::from(game.board.black.captures).into()),
+                ("white", i64::from(game.board.white.captures).into()),
+            ]),
+        ),
+        (
+            "moves",
+            Json::Array(
+                game.variations
+                    .line(game.variations.current)
+                    .iter()
+                    .map(move_json)
+                    .collect(),
+            ),
+        ),
+        ("board", board_json(&game.board)),
+    ])
+}
+
+// Games played by the clients of the server, each client plays both sides or asks the computer
+#[derive(Default)]
+pub struct Server {
+    games: BTreeMap<usize, Game>,
+    next_id: usize,
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Server::default()
+    }
+
+    // Route the request to the endpoints
+    // -- POST /games, GET|DELETE /games/{id}, POST /games/{id}/moves,
+    // -- POST /games/{id}/computer, POST /games/{id}/resign, POST /games/{id}/draw
+    // -- and GET /games/{id}/legal_moves
+    pub fn handle(&mut self, request: &Request) -> Response {
+        let path = request.path.split('?').next().unwrap_or_default();
+        let segments: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+        let method = request.method.as_str();
+        match segments[..] {
+            ["games"] => match method {
+                "POST" => self.create_game(request),
+                "GET" => Response::json(
+                    200,
+                    Json::object(vec![(
+                        "games",
+                        Json::Array(self.games.keys().map(|id| (*id as i64).into()).collect()),
+                    )]),
+                ),
+                _ => Response::error(405, "Use POST to create a game"),
+            },
+            ["games", id, ..] => {
+                let Some((id, game)) = id
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|id| self.games.get_mut(&id).map(|game| (id, game)))
+                else {
+                    return Response::error(404, &format!("Unknown game {}", id));
+                };
+                match (method, &segments[2..]) {
+                    ("GET", []) => Response::json(200, game_json(id, game)),
+                    ("DELETE", []) => {
+                        self.games.remove(&id);
+                        Response {
+                            status: 204,
+                            body: None,
+                        }
+                    }
+                    ("POST", ["moves"]) => play_move(id, game, request),
+                    ("POST", ["computer"]) => play_computer(id, game, request),
+                    ("POST", ["resign"]) => resign(id, game, request),
+                    ("POST", ["draw"]) => offer_draw(id, game),
+                    ("GET", ["legal_moves"]) => legal_moves(game),
+                    (
+                        _,
+                        [] | ["moves"] | ["computer"] | ["resign"] | ["draw"] | ["legal_moves"],
+                    ) => Response::error(405, "Method not allowed"),
+                    _ => Response::error(404, &format!("Unknown path {}", path)),
+                }
+            }
+            _ => Response::error(404, &format!("Unknown path {}", path)),
+        }
+    }
+
+    // Body: capture, game_ending_capture, no_double_three, move_limit and algorithm, all optional
+    fn create_game(&mut self, request: &Request) -> Response {
+        let body = match request.json() {
+            Ok(body) => body,
+            Err(response) => return response,
+        };
+        let mut game = Game::default();
+        if let Some(name) = body.get("algorithm") {
+            match name.as_str().map(str::parse::<Algorithm>) {
+                Some(Ok(algorithm)) => {
+                    game.algorithm_index = Algorithm::ALL
+                        .iter()
+                        .position(|candidate| *candidate == algorithm)
+                }
+                Some(Err(error)) => return Response::error(400, &error),
+                None => return Response::error(400, "The algorithm must be a string"),
+            }
+        }
+        let rules = match parse_rules(&body) {
+            Ok(rules) => rules,
+            Err(response) => return response,
+        };
+        game.rules = rules;
+        // The move limit of the options is applied when the game starts
+        game.start(GameMode::PvP);
+        game.rules.move_limit = rules.move_limit;
+
+        self.next_id += 1;
+        let id = self.next_id;
+        let response = Response::json(201, game_json(id, &game));
+        self.games.insert(id, game);
+        response
+    }
+
+    // Answer each connection with one response, the requests are handled one at a time
+    pub fn serve(&mut self, listener: TcpListener) {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => self.answer(stream),
+                Err(error) => println!("connection failed: {}", error),
+            }
+        }
+    }
+
+    fn answer(&mut self, mut stream: TcpStream) {
+        if let Err(error) = stream
+            .set_read_timeout(Some(CONNECTION_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(CONNECTION_TIMEOUT)))
+        {
+            println!("connection failed: {}", error);
+            return;
+        }
+        let response = match read_request(&stream) {
+            Ok(request) => {
+                let response = self.handle(&request);
+                println!("{} {} {}", request.method, request.path, response.status);
+                response
+            }
+            Err(error) => Response::error(400, &error),
+        };
+        if let Err(error) = response.write(&mut stream) {
+            println!("failed to answer: {}", error);
+        }
+    }
+}
+
+// Missing rules keep their default value
+fn parse_rules(body: &Json) -> Result<RuleSet, Response> {
+    let defaults = RuleSet::default();
+    let flag = |key: &str, default: bool| match body.get(key) {
+        None => Ok(default),
+        Some(value) => value
+            .as_bool()
+            .ok_or_else(|| Response::error(400, &format!("{} must be a boolean", key))),
+    };
+    let move_limit = match body.get("move_limit") {
+        None | Some(Json::Null) => None,
+        Some(limit) => match limit.as_i64().and_then(|limit| u16::try_from(limit).ok()) {
+            Some(limit) if limit > 0 => Some(limit),
+            _ => {
+                return Err(Response::error(
+                    400,
+                    "move_limit must be a positive integer",
+                ))
+            }
+        },
+    };
+    Ok(RuleSet {
+        capture: flag("capture", defaults.capture)?,
+        game_ending_capture: flag("game_ending_capture", defaults.game_ending_capture)?,
+        no_double_three: flag("no_double_three", defaults.no_double_three)?,
+        move_limit,
+    })
+}
+
+fn running_game(game: &Game) -> Result<(), Response> {
+    if game.winner == Winner::None {
+        Ok(())
+    } else {
+        Err(Response::error(409, "The game is over"))
+    }
+}
+
+// Body: x and y of the movement of the player to move
+fn play_move(id: usize, game: &mut Game, request: &Request) -> Response {
+    let body = match request.json().and_then(|body| {
+        running_game(game)?;
+        Ok(body)
+    }) {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let coordinate = |key: &str| body.get(key).and_then(Json::as_i64);
+    let (Some(x), Some(y)) = (coordinate("x"), coordinate("y")) else {
+        return Response::error(400, "x and y must be integers");
+    };
+    let coordinates = Coordinates {
+        x: x.clamp(-1, BOARD_SIZE as i64) as i16,
+        y: y.clamp(-1, BOARD_SIZE as i64) as i16,
+    };
+    if !coordinates.is_on_board() {
+        return Response::error(422, &format!("{}x{} is out of the board", x, y));
+    }
+    match game.play_player_quietly(coordinates) {
+        Ok(_) => Response::json(200, game_json(id, game)),
+        Err(reason) => Response::json(
+            422,
+            Json::object(vec![
+                ("error", format!("Illegal move {}", coordinates).into()),
+                ("reason", reason.to_string().into()),
+            ]),
+        ),
+    }
+}
+
+// Body: depth of the search and time_ms, the best move found is played when the time is used
+// -- the time is limited by default, and can't be longer than the maximum
+fn play_computer(id: usize, game: &mut Game, request: &Request) -> Response {
+    let body = match request.json().and_then(|body| {
+        running_game(game)?;
+        Ok(body)
+    }) {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let depth = match body.get("depth") {
+        None => DEPTH,
+        Some(depth) => match depth.as_i64() {
+            Some(depth) if (1..=MAX_DEPTH as i64).contains(&depth) => depth as usize,
+            _ => {
+                return Response::error(
+                    400,
+                    &format!("depth must be an integer between 1 and {}", MAX_DEPTH),
+                )
+            }
+        },
+    };
+    let budget = match body.get("time_ms") {
+        None => DEFAULT_SEARCH_TIME,
+        Some(time) => match time.as_i64() {
+            Some(time) if time > 0 => Duration::from_millis(time as u64).min(MAX_SEARCH_TIME),
+            _ => return Response::error(400, "time_ms must be a positive integer"),
+        },
+    };
+
+    let computer = std::mem::take(&mut game.computer);
+    game.play_time = Instant::now();
+    let thinking = computer.play_in_background(
+        game.algorithm(),
+        game.rules,
+        game.board.clone(),
+        depth,
+        game.current_player,
+    );
+    while !thinking.is_finished() && thinking.started.elapsed() < budget {
+        thread::sleep(Duration::from_millis(5));
+    }
+    thinking.move_now();
+    let (computer, play_result) = thinking.join();
+    game.computer = computer;
+    let evaluation = play_result.as_ref().ok().cloned();
+    let moves = game.rock_move.len();
+    game.apply_computer_play(play_result);
+    let movement = if game.rock_move.len() > moves {
+        game.variations
+            .line(game.variations.current)
+            .last()
+            .copied()
+    } else {
+        None
+    };
+    Response::json(
+        200,
+        Json::object(vec![
+            ("move", movement.as_ref().map_or(Json::Null, move_json)),
+            (
+                "score",
+                evaluation
+                    .as_ref()
+                    .map(|evaluation| i64::from(evaluation.score))
+                    .into(),
+            ),
+            (
+                "mate_in",
+                evaluation
+                    .as_ref()
+                    .and_then(|evaluation| evaluation.mate_in())
+                    .map(i64::from)
+                    .into(),
+            ),
+            (
+                "line",
+                Json::Array(
+                    evaluation
+                        .map(|evaluation| evaluation.movements.iter().map(move_json).collect())
+                        .unwrap_or_default(),
+                ),
+            ),
+            ("time_ms", (budget.as_millis() as i64).into()),
+            ("game", game_json(id, game)),
+        ]),
+    )
+}
+
+// Body: player that gives up, the player to move by default
+fn resign(id: usize, game: &mut Game, request: &Request) -> Response {
+    let body = match request.json().and_then(|body| {
+        running_game(game)?;
+        Ok(body)
+    }) {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let player = match body.get("player").map(|player| player.as_str()) {
+        None => game.current_player,
+        Some(Some("black")) => Player::Black,
+        Some(Some("white")) => Player::White,
+        Some(_) => return Response::error(400, "player must be black or white"),
+    };
+    game.resign_player(player);
+    Response::json(200, game_json(id, game))
+}
+
+// The computer answers the draw offered by the client from the score of its last move
+fn offer_draw(id: usize, game: &mut Game) -> Response {
+    if let Err(response) = running_game(game) {
+        return response;
+    }
+    let accepted = game.computer_accepts_draw();
+    game.answer_draw(accepted);
+    Response::json(
+        200,
+        Json::object(vec![
+            ("accepted", accepted.into()),
+            ("game", game_json(id, game)),
+        ]),
+    )
+}
+
+// Every empty intersection for the player to move, with the reason of the illegal ones
+fn legal_moves(game: &Game) -> Response {
+    let mut moves = vec![];
+    for y in 0..BOARD_SIZE {
+        for x in 0..BOARD_SIZE {
+            if game.board.get(x, y) != Rock::None {
+                continue;
+            }
+            let movement = Move {
+                coordinates: Coordinates { x, y },
+                player: game.current_player,
+            };
+            let reason = game.board.illegal_move_reason(&game.rules, &movement);
+            moves.push(Json::object(vec![
+                ("x", i64::from(x).into()),
+                ("y", i64::from(y).into()),
+                ("legal", reason.is_none().into()),
+                (
+                    "reason",
+                    reason.as_ref().map(|reason| reason.description()).into(),
+                ),
+            ]));
+        }
+    }
+    Response::json(
+        200,
+        Json::object(vec![
+            ("player", player_name(game.current_player).into()),
+            ("moves", Json::Array(moves)),
+        ]),
+    )
+}
@@ -0,0 +1,192 @@
+use crate::{board::Move, player::Player};
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct VariationNode {
+    pub movement: Move,
+    pub parent: Option<usize>,
+    // The first child continues the main line, the others are variations
+    pub children: Vec<usize>,
+}
+
+// Every move played in a game, with the alternatives explored from each position
+#[derive(Debug, Clone, Default)]
+pub struct VariationTree {
+    pub nodes: Vec<VariationNode>,
+    // First moves of the game, the first one starts the main line
+    pub roots: Vec<usize>,
+    // Last move of the current position, None before the first move
+    pub current: Option<usize>,
+}
+
+impl VariationTree {
+    pub fn children(&self, node: Option<usize>) -> &[usize] {
+        match node {
+            Some(node) => &self.nodes[node].children,
+            None => &self.roots,
+        }
+    }
+
+    fn children_mut(&mut self, node: Option<usize>) -> &mut Vec<usize> {
+        match node {
+            Some(node) => &mut self.nodes[node].children,
+            None => &mut self.roots,
+        }
+    }
+
+    // Moves that can follow the current position, the first one is the main line
+    pub fn next_moves(&self) -> Vec<Move> {
+        self.children(self.current)
+            .iter()
+            .map(|child| self.nodes[*child].movement)
+            .collect()
+    }
+
+    // Follow the movement if it was already played from this position or add a new variation
+    pub fn play(&mut self, movement: Move) -> usize {
+        let node = self.add(self.current, movement);
+        self.current = Some(node);
+        node
+    }
+
+    // Add the movement after the node if it's not there yet, without changing the current position
+    pub fn add(&mut self, parent: Option<usize>, movement: Move) -> usize {
+        let existing = self
+            .children(parent)
+            .iter()
+            .find(|child| self.nodes[**child].movement == movement)
+            .copied();
+        existing.unwrap_or_else(|| {
+            let node = self.nodes.len();
+            self.nodes.push(VariationNode {
+                movement,
+                parent,
+                children: vec![],
+            });
+            self.children_mut(parent).push(node);
+            node
+        })
+    }
+
+    // All the nodes with each parent before its children, the main line of each position first
+    pub fn preorder(&self) -> Vec<usize> {
+        let mut nodes = vec![];
+        let mut stack: Vec<usize> = self.roots.iter().rev().copied().collect();
+        while let Some(node) = stack.pop() {
+            nodes.push(node);
+            stack.extend(self.nodes[node].children.iter().rev());
+        }
+        nodes
+    }
+
+    // Go back to the previous position and return the undone movement
+    pub fn back(&mut self) -> Option<Move> {
+        let node = &self.nodes[self.current?];
+        self.current = node.parent;
+        Some(node.movement)
+    }
+
+    pub fn rewind(&mut self) {
+        self.current = None;
+    }
+
+    // Moves from the start of the game to the position after the node
+    pub fn line(&self, node: Option<usize>) -> Vec<Move> {
+        let mut line = vec![];
+        let mut node = node;
+        while let Some(index) = node {
+            line.push(self.nodes[index].movement);
+            node = self.nodes[index].parent;
+        }
+        line.reverse();
+        line
+    }
+
+    // Moves after the node, always following the main line of each position
+    pub fn continuation(&self, node: Option<usize>) -> Vec<Move> {
+        let mut continuation = vec![];
+        let mut node = node;
+        while let Some(child) = self.children(node).first() {
+            continuation.push(self.nodes[*child].movement);
+            node = Some(*child);
+        }
+        continuation
+    }
+
+    pub fn is_main_line(&self) -> bool {
+        let mut node = self.current;
+        while let Some(index) = node {
+            let parent = self.nodes[index].parent;
+            if self.children(parent).first() != Some(&index) {
+                return false;
+            }
+            node = parent;
+        }
+        true
+    }
+
+    // Make the line to the current position the main line
+    pub fn promote(&mut self) {
+        let mut node = self.current;
+        while let Some(index) = node {
+            let parent = self.nodes[index].parent;
+            let siblings = self.children_mut(parent);
+            if let Some(position) = siblings.iter().position(|child| *child == index) {
+                siblings.remove(position);
+                siblings.insert(0, index);
+            }
+            node = parent;
+        }
+    }
+
+    fn write_move(&self, f: &mut fmt::Formatter<'_>, node: usize, number: usize) -> fmt::Result {
+        let movement = self.nodes[node].movement;
+        write!(
+            f,
+            "{}. {} {}",
+            number,
+            if movement.player == Player::Black {
+                "B"
+            } else {
+                "W"
+            },
+            movement.coordinates
+        )
+    }
+
+    // Main line from the siblings, each variation is written in parentheses after the move it replaces
+    fn write_line(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        siblings: &[usize],
+        number: usize,
+    ) -> fmt::Result {
+        let mut siblings = siblings;
+        let mut number = number;
+        let mut separator = "";
+        while let Some(main) = siblings.first() {
+            write!(f, "{}", separator)?;
+            self.write_move(f, *main, number)?;
+            for variation in siblings.iter().skip(1) {
+                write!(f, " (")?;
+                self.write_move(f, *variation, number)?;
+                let children = &self.nodes[*variation].children;
+                if !children.is_empty() {
+                    write!(f, " ")?;
+                    self.write_line(f, children, number + 1)?;
+                }
+                write!(f, ")")?;
+            }
+            siblings = &self.nodes[*main].children;
+            number += 1;
+            separator = " ";
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for VariationTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_line(f, &self.roots, 1)
+    }
+}
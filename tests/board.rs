@@ -1,7 +1,7 @@
 use std::collections::BTreeSet;
 
 use gomoku::{
-    board::{Board, Coordinates, Move},
+    board::{Board, Coordinates, DrawReason, IllegalMove, Move, WinReason},
     constants::{BOARD_SIZE, BOARD_SIZE_USIZE, DIRECTIONS},
     player::Player,
     rock::Rock,
@@ -319,6 +319,36 @@ fn open_intersections_one_pawn() {
     );
 }
 
+#[test]
+fn open_intersections_radius_two() {
+    let mut board = Board::default();
+    board.set_move(
+        &RuleSet::default(),
+        &Move {
+            player: Player::Black,
+            coordinates: CENTER,
+        },
+    );
+    let intersections = board.open_intersections_within(2);
+    assert_eq!(intersections.len(), 24);
+    assert!(intersections.contains(&coord!(CENTER.x - 2, CENTER.y + 2)));
+    assert!(intersections.contains(&coord!(CENTER.x + 1, CENTER.y - 2)));
+    assert!(!intersections.contains(&CENTER));
+}
+
+#[test]
+fn open_intersections_radius_on_border() {
+    let mut board = Board::default();
+    board.set_move(
+        &RuleSet::default(),
+        &Move {
+            player: Player::Black,
+            coordinates: coord!(0, 0),
+        },
+    );
+    assert_eq!(board.open_intersections_within(3).len(), 15);
+}
+
 // * Player rocks
 
 #[test]
@@ -1464,3 +1494,279 @@ fn captured_five_in_a_row_diagonal_4() {
     assert!(!board.has_uncaptured_five_in_a_row(&RuleSet::default(), Player::Black));
     assert!(!board.is_winning(&rules, Player::Black));
 }
+
+#[test]
+fn win_reasons() {
+    let rules = RuleSet::default();
+    let mut board = Board::default();
+    assert_eq!(board.win_reason(&rules, Player::Black), None);
+
+    set_many!(
+        mut board,
+        Player::Black,
+        (1, 2),
+        (2, 2),
+        (3, 2),
+        (4, 2),
+        (5, 2)
+    );
+    assert_eq!(
+        board.win_reason(&rules, Player::Black),
+        Some(WinReason::FiveInARow)
+    );
+
+    board.white.captures = 10;
+    assert_eq!(
+        board.win_reason(&rules, Player::White),
+        Some(WinReason::Captures)
+    );
+    let no_capture = RuleSet {
+        capture: false,
+        ..Default::default()
+    };
+    assert_eq!(board.win_reason(&no_capture, Player::White), None);
+}
+
+// * Draw
+
+// Fill the board without any capture, except the empty intersections
+fn fill_board(empty: &[Coordinates], player_at: impl Fn(i16, i16) -> Player) -> Board {
+    let rules = RuleSet {
+        capture: false,
+        game_ending_capture: false,
+        no_double_three: false,
+        move_limit: None,
+    };
+    let mut board = Board::default();
+    for x in 0..BOARD_SIZE {
+        for y in 0..BOARD_SIZE {
+            if !empty.contains(&coord!(x, y)) {
+                board.set_move(
+                    &rules,
+                    &Move {
+                        player: player_at(x, y),
+                        coordinates: coord!(x, y),
+                    },
+                );
+            }
+        }
+    }
+    board
+}
+
+fn pairs_pattern(x: i16, y: i16) -> Player {
+    if (x / 2 + y) % 2 == 0 {
+        Player::Black
+    } else {
+        Player::White
+    }
+}
+
+#[test]
+fn no_draw_on_an_empty_board() {
+    let board = Board::default();
+    assert_eq!(board.draw_reason(&RuleSet::default(), Player::Black), None);
+}
+
+#[test]
+fn draw_on_a_full_board() {
+    let board = fill_board(&[], pairs_pattern);
+    assert_eq!(
+        board.draw_reason(&RuleSet::default(), Player::Black),
+        Some(DrawReason::FullBoard)
+    );
+}
+
+#[test]
+fn draw_without_legal_move() {
+    // The last intersection would be a recursive capture for black
+    let board = fill_board(&[coord!(9, 9)], |x, y| match (x, y) {
+        (8, 9) | (11, 9) => Player::White,
+        (10, 9) => Player::Black,
+        _ => pairs_pattern(x, y),
+    });
+    assert_eq!(
+        board.draw_reason(&RuleSet::default(), Player::Black),
+        Some(DrawReason::NoLegalMove(Player::Black))
+    );
+    let rules = RuleSet {
+        capture: false,
+        ..Default::default()
+    };
+    assert_eq!(board.draw_reason(&rules, Player::Black), None);
+}
+
+#[test]
+fn draw_on_move_limit() {
+    let mut board = Board::default();
+    board.set_move(
+        &RuleSet::default(),
+        &Move {
+            player: Player::Black,
+            coordinates: CENTER,
+        },
+    );
+    let rules = RuleSet {
+        move_limit: Some(2),
+        ..Default::default()
+    };
+    assert!(!board.reached_move_limit(&rules));
+    assert_eq!(board.draw_reason(&rules, Player::White), None);
+    board.set_move(
+        &rules,
+        &Move {
+            player: Player::White,
+            coordinates: coord!(CENTER.x + 1, CENTER.y),
+        },
+    );
+    assert!(board.reached_move_limit(&rules));
+    assert_eq!(
+        board.draw_reason(&rules, Player::Black),
+        Some(DrawReason::MoveLimit(2))
+    );
+    assert!(!board.reached_move_limit(&RuleSet::default()));
+}
+
+// * Illegal move reasons
+
+#[test]
+fn illegal_move_reason_legal_move() {
+    let board = Board::default();
+    let movement = Move {
+        player: Player::Black,
+        coordinates: CENTER,
+    };
+    assert_eq!(
+        board.illegal_move_reason(&RuleSet::default(), &movement),
+        None
+    );
+    assert_eq!(
+        board.illegal_opening_move_reason(&RuleSet::default(), &movement),
+        None
+    );
+}
+
+#[test]
+fn illegal_move_reason_occupied() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::White, (CENTER.x, CENTER.y));
+    let movement = Move {
+        player: Player::Black,
+        coordinates: CENTER,
+    };
+    assert_eq!(
+        board.illegal_move_reason(&RuleSet::default(), &movement),
+        Some(IllegalMove::Occupied)
+    );
+}
+
+#[test]
+fn illegal_move_reason_double_free_three() {
+    let mut board = Board::default();
+    set_many!(
+        mut board,
+        Player::Black,
+        (CENTER.x, CENTER.y - 2),
+        (CENTER.x, CENTER.y - 1),
+        (CENTER.x + 1, CENTER.y),
+        (CENTER.x + 2, CENTER.y)
+    );
+    let movement = Move {
+        player: Player::Black,
+        coordinates: CENTER,
+    };
+    let reason = board.illegal_move_reason(&RuleSet::default(), &movement);
+    let threes = match reason {
+        Some(IllegalMove::DoubleFreeThree(threes)) => threes,
+        _ => panic!("Expected a double free three, got {:?}", reason),
+    };
+    assert_eq!(threes.len(), 2);
+    assert!(threes.contains(&[
+        CENTER,
+        coord!(CENTER.x, CENTER.y - 1),
+        coord!(CENTER.x, CENTER.y - 2)
+    ]));
+    assert!(threes.contains(&[
+        CENTER,
+        coord!(CENTER.x + 1, CENTER.y),
+        coord!(CENTER.x + 2, CENTER.y)
+    ]));
+    // Allowed without the rule
+    let rules = RuleSet {
+        no_double_three: false,
+        ..Default::default()
+    };
+    assert_eq!(board.illegal_move_reason(&rules, &movement), None);
+}
+
+#[test]
+fn movement_free_threes_match_double_free_three() {
+    let mut board = Board::default();
+    // Subject example
+    set_many!(
+        mut board,
+        Player::Black,
+        (CENTER.x - 3, CENTER.y - 3),
+        (CENTER.x - 2, CENTER.y - 2),
+        (CENTER.x + 1, CENTER.y),
+        (CENTER.x + 2, CENTER.y)
+    );
+    let threes = board.movement_free_threes(&CENTER, Player::Black);
+    assert!(board.movement_create_double_free_three(&CENTER, Player::Black));
+    assert_eq!(threes.len(), 2);
+    assert!(threes.contains(&[
+        CENTER,
+        coord!(CENTER.x - 2, CENTER.y - 2),
+        coord!(CENTER.x - 3, CENTER.y - 3)
+    ]));
+    // A single three is not enough
+    assert_eq!(
+        board
+            .movement_free_threes(&coord!(CENTER.x + 3, CENTER.y), Player::Black)
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn illegal_move_reason_recursive_capture() {
+    let mut board = Board::default();
+    set_many!(
+        mut board,
+        Player::Black,
+        (CENTER.x, CENTER.y),
+        (CENTER.x + 3, CENTER.y)
+    );
+    set_many!(mut board, Player::White, (CENTER.x + 1, CENTER.y));
+    let movement = Move {
+        player: Player::White,
+        coordinates: coord!(CENTER.x + 2, CENTER.y),
+    };
+    let reason = board.illegal_move_reason(&RuleSet::default(), &movement);
+    assert_eq!(
+        reason,
+        Some(IllegalMove::RecursiveCapture(vec![
+            coord!(CENTER.x + 3, CENTER.y),
+            coord!(CENTER.x + 1, CENTER.y),
+            coord!(CENTER.x, CENTER.y)
+        ]))
+    );
+    assert_eq!(reason.unwrap().rocks().len(), 3);
+}
+
+#[test]
+fn illegal_opening_move_reason_out_of_area() {
+    let board = Board::default();
+    let movement = Move {
+        player: Player::Black,
+        coordinates: coord!(3, 9),
+    };
+    assert_eq!(
+        board.illegal_move_reason(&RuleSet::default(), &movement),
+        None
+    );
+    assert_eq!(
+        board.illegal_opening_move_reason(&RuleSet::default(), &movement),
+        Some(IllegalMove::OutOfOpeningArea)
+    );
+}
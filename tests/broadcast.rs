@@ -0,0 +1,227 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use gomoku::{
+    board::{Coordinates, DrawReason, Move, WinReason},
+    broadcast::{Broadcaster, Event, Spectator},
+    clock::TimeControl,
+    game::{Game, GameMode, Winner},
+    player::Player,
+    rules::RuleSet,
+};
+
+macro_rules! movement {
+    ($x: expr, $y: expr, $player: expr) => {
+        Move {
+            player: $player,
+            coordinates: Coordinates { x: $x, y: $y },
+        }
+    };
+}
+
+// Poll the spectator until it received the events, an error, or a second passed
+fn wait_events(spectator: &Spectator, count: usize) -> (Vec<Event>, Option<String>) {
+    let started = Instant::now();
+    let mut received = vec![];
+    loop {
+        let (events, error) = spectator.receive();
+        received.extend(events);
+        if received.len() >= count || error.is_some() || started.elapsed() > Duration::from_secs(1)
+        {
+            return (received, error);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn temporary_file(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("gomoku_{}_{}", std::process::id(), name))
+        .to_string_lossy()
+        .to_string()
+}
+
+#[test]
+fn events_round_trip() {
+    let events = vec![
+        Event::Start {
+            rules: RuleSet {
+                no_double_three: false,
+                move_limit: Some(120),
+                ..Default::default()
+            },
+            time_control: TimeControl::ByoYomi {
+                time: Duration::from_secs(300),
+                periods: 5,
+                period: Duration::from_secs(30),
+            },
+        },
+        Event::Move(movement!(9, 9, Player::Black)),
+        Event::Evaluation {
+            score: -42,
+            mate_in: Some(3),
+            line: vec![
+                movement!(8, 8, Player::White),
+                movement!(10, 10, Player::Black),
+            ],
+        },
+        Event::Evaluation {
+            score: 0,
+            mate_in: None,
+            line: vec![],
+        },
+        Event::Clock {
+            times: [Duration::from_millis(295_000), Duration::from_millis(1_500)],
+            periods: [5, 2],
+        },
+        Event::Undo,
+        Event::End(Winner::White(WinReason::Resignation)),
+        Event::End(Winner::Black(WinReason::Time)),
+        Event::End(Winner::Draw(DrawReason::MoveLimit(120))),
+        Event::End(Winner::Draw(DrawReason::NoLegalMove(Player::Black))),
+    ];
+    for event in events {
+        assert_eq!(event.to_string().parse::<Event>(), Ok(event));
+    }
+}
+
+#[test]
+fn invalid_events() {
+    assert!("start true true".parse::<Event>().is_err());
+    assert!("move 9x9".parse::<Event>().is_err());
+    assert!("eval high -".parse::<Event>().is_err());
+    assert!("clock 1 2 3".parse::<Event>().is_err());
+    assert!("end purple".parse::<Event>().is_err());
+    assert!("checkmate".parse::<Event>().is_err());
+}
+
+#[test]
+fn late_spectators_receive_the_game() {
+    let mut broadcaster = Broadcaster::start("127.0.0.1:0");
+    assert_eq!(broadcaster.error, None);
+    let address = broadcaster.local_address().unwrap();
+    broadcaster.send(Event::Start {
+        rules: RuleSet::default(),
+        time_control: TimeControl::Unlimited,
+    });
+    broadcaster.send(Event::Move(movement!(9, 9, Player::Black)));
+
+    let spectator = Spectator::watch(&address);
+    assert_eq!(spectator.error, None);
+    broadcaster.send(Event::Move(movement!(8, 8, Player::White)));
+    let (events, error) = wait_events(&spectator, 3);
+    assert_eq!(error, None);
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[2], Event::Move(movement!(8, 8, Player::White)));
+    assert_eq!(broadcaster.spectators(), 1);
+
+    // A new game is sent alone to the next spectators
+    broadcaster.send(Event::Start {
+        rules: RuleSet::default(),
+        time_control: TimeControl::Unlimited,
+    });
+    let late = Spectator::watch(&address);
+    broadcaster.send(Event::Undo);
+    assert_eq!(wait_events(&late, 2).0.len(), 2);
+}
+
+#[test]
+fn follow_a_file() {
+    let path = temporary_file("follow.txt");
+    let target = format!("file:{}", path);
+    let mut broadcaster = Broadcaster::start(&target);
+    assert_eq!(broadcaster.error, None);
+    broadcaster.send(Event::Move(movement!(9, 9, Player::Black)));
+
+    let spectator = Spectator::watch(&target);
+    assert_eq!(wait_events(&spectator, 1).0.len(), 1);
+    // The lines written after the end of the file are followed
+    broadcaster.send(Event::End(Winner::Black(WinReason::FiveInARow)));
+    assert_eq!(
+        wait_events(&spectator, 1),
+        (vec![Event::End(Winner::Black(WinReason::FiveInARow))], None)
+    );
+    drop(spectator);
+    let _ = std::fs::remove_file(path);
+
+    assert!(Spectator::watch("file:/nonexistent/gomoku.txt")
+        .error
+        .is_some());
+}
+
+#[test]
+fn spectator_follows_the_game() {
+    let path = temporary_file("game.txt");
+    let target = format!("file:{}", path);
+    let mut game = Game {
+        broadcast_address: target.clone(),
+        ..Default::default()
+    };
+    game.toggle_broadcast();
+    game.start(GameMode::PvP);
+    game.play_player(Coordinates { x: 9, y: 9 });
+    game.play_player(Coordinates { x: 8, y: 8 });
+    game.play_player(Coordinates { x: 10, y: 10 });
+    game.undo_move();
+    game.game_draw(DrawReason::Agreement);
+    let (moves, player) = (game.rock_move.clone(), game.current_player);
+    // The navigation in the finished game is not broadcasted
+    game.undo_move();
+    game.jump_to_move(0);
+    game.redo_move();
+
+    let mut spectator = Game {
+        broadcast_address: target,
+        ..Default::default()
+    };
+    spectator.watch();
+    let started = Instant::now();
+    while spectator.winner == Winner::None && started.elapsed() < Duration::from_secs(1) {
+        spectator.update_spectator();
+        thread::sleep(Duration::from_millis(10));
+    }
+    thread::sleep(Duration::from_millis(50));
+    spectator.update_spectator();
+    assert_eq!(spectator.winner, Winner::Draw(DrawReason::Agreement));
+    assert_eq!(spectator.rock_move, moves);
+    assert_eq!(spectator.current_player, player);
+    assert_eq!(spectator.rules, game.rules);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn invalid_moves_stop_the_spectator() {
+    for (name, invalid) in [
+        ("occupied.txt", movement!(9, 9, Player::White)),
+        ("outside.txt", movement!(30, 30, Player::White)),
+    ] {
+        let path = temporary_file(name);
+        let target = format!("file:{}", path);
+        let mut broadcaster = Broadcaster::start(&target);
+        broadcaster.send(Event::Move(movement!(9, 9, Player::Black)));
+        broadcaster.send(Event::Move(invalid));
+        broadcaster.send(Event::Move(movement!(10, 10, Player::White)));
+
+        let mut spectator = Game {
+            broadcast_address: target,
+            ..Default::default()
+        };
+        spectator.watch();
+        let started = Instant::now();
+        while spectator.spectator.as_ref().unwrap().error.is_none()
+            && started.elapsed() < Duration::from_secs(1)
+        {
+            spectator.update_spectator();
+            thread::sleep(Duration::from_millis(10));
+        }
+        let error = spectator.spectator.as_ref().unwrap().error.clone();
+        assert!(error.is_some_and(|error| error.contains("Invalid move")));
+        // The next events are not applied
+        spectator.update_spectator();
+        assert_eq!(spectator.rock_move, vec![Coordinates { x: 9, y: 9 }]);
+        drop(broadcaster);
+        let _ = std::fs::remove_file(path);
+    }
+}
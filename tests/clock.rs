@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use gomoku::{
+    clock::{format_time, Clock, TimeControl},
+    player::Player,
+};
+
+fn seconds(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+#[test]
+fn time_control_from_text() {
+    assert_eq!(
+        "unlimited".parse::<TimeControl>(),
+        Ok(TimeControl::Unlimited)
+    );
+    assert_eq!(
+        "sudden:300".parse::<TimeControl>(),
+        Ok(TimeControl::SuddenDeath(seconds(300)))
+    );
+    assert_eq!(
+        "fischer:180+2".parse::<TimeControl>(),
+        Ok(TimeControl::Fischer {
+            time: seconds(180),
+            increment: seconds(2)
+        })
+    );
+    assert_eq!(
+        "byoyomi:300+5x30".parse::<TimeControl>(),
+        Ok(TimeControl::ByoYomi {
+            time: seconds(300),
+            periods: 5,
+            period: seconds(30)
+        })
+    );
+    assert!("fischer:180".parse::<TimeControl>().is_err());
+    assert!("blitz".parse::<TimeControl>().is_err());
+}
+
+#[test]
+fn unlimited_time_never_ends() {
+    let mut clock = Clock::default();
+    assert!(clock.spend(Player::Black, seconds(3600)));
+    assert_eq!(clock.search_budget(Player::Black), None);
+}
+
+#[test]
+fn sudden_death_loss_on_time() {
+    let mut clock = Clock::new(TimeControl::SuddenDeath(seconds(60)));
+    assert!(clock.spend(Player::Black, seconds(40)));
+    assert_eq!(clock.times[Player::Black as usize].main, seconds(20));
+    assert_eq!(clock.times[Player::White as usize].main, seconds(60));
+    assert!(!clock.spend(Player::Black, seconds(20)));
+    assert!(clock.is_flagged(Player::Black));
+    assert!(!clock.is_flagged(Player::White));
+}
+
+#[test]
+fn fischer_adds_the_increment() {
+    let mut clock = Clock::new(TimeControl::Fischer {
+        time: seconds(60),
+        increment: seconds(5),
+    });
+    assert!(clock.spend(Player::White, seconds(10)));
+    assert_eq!(clock.times[Player::White as usize].main, seconds(55));
+    // No increment for a move played too late
+    assert!(!clock.spend(Player::White, seconds(55)));
+    assert_eq!(clock.times[Player::White as usize].main, Duration::ZERO);
+}
+
+#[test]
+fn byo_yomi_periods() {
+    let mut clock = Clock::new(TimeControl::ByoYomi {
+        time: seconds(60),
+        periods: 3,
+        period: seconds(10),
+    });
+    // Main time is over, but the move is played in the first period
+    assert!(clock.spend(Player::Black, seconds(65)));
+    assert_eq!(clock.times[Player::Black as usize].periods, 3);
+    // The period is reset on each move
+    assert!(clock.spend(Player::Black, seconds(9)));
+    assert_eq!(clock.times[Player::Black as usize].periods, 3);
+    // Two periods used completely
+    assert!(clock.spend(Player::Black, seconds(25)));
+    assert_eq!(clock.times[Player::Black as usize].periods, 1);
+    assert!(!clock.spend(Player::Black, seconds(10)));
+    assert!(clock.is_flagged(Player::Black));
+}
+
+#[test]
+fn search_budget_from_remaining_time() {
+    let clock = Clock::new(TimeControl::SuddenDeath(seconds(300)));
+    assert_eq!(clock.search_budget(Player::Black), Some(seconds(10)));
+    let clock = Clock::new(TimeControl::Fischer {
+        time: seconds(60),
+        increment: seconds(4),
+    });
+    assert_eq!(clock.search_budget(Player::Black), Some(seconds(5)));
+
+    // Never more than half of the time left
+    let mut clock = Clock::new(TimeControl::Fischer {
+        time: seconds(10),
+        increment: seconds(20),
+    });
+    assert_eq!(clock.search_budget(Player::Black), Some(seconds(5)));
+    let mut byo_yomi = Clock::new(TimeControl::ByoYomi {
+        time: seconds(60),
+        periods: 3,
+        period: seconds(10),
+    });
+    byo_yomi.spend(Player::White, seconds(60));
+    assert_eq!(
+        byo_yomi.search_budget(Player::White),
+        Some(Duration::from_millis(5000))
+    );
+    clock.spend(Player::Black, seconds(10));
+    assert_eq!(clock.search_budget(Player::Black), Some(Duration::ZERO));
+}
+
+#[test]
+fn format_time_with_tenths_at_the_end() {
+    assert_eq!(format_time(seconds(300)), "5:00");
+    assert_eq!(format_time(seconds(75)), "1:15");
+    assert_eq!(format_time(Duration::from_millis(9450)), "0:09.4");
+}
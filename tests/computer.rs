@@ -0,0 +1,739 @@
+use gomoku::{
+    board::{Board, Coordinates, Move},
+    computer::{
+        mate_in, Algorithm, Computer, MoveOrdering, Randomness, SearchOptions, SortedMove,
+        Strength, STRENGTH_LEVELS,
+    },
+    constants::{BOARD_SIZE, WIN_SCORE},
+    patterns::PatternCount,
+    player::Player,
+    rock::Rock,
+    rules::RuleSet,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+macro_rules! coord {
+    ($x: expr, $y: expr) => {{
+        use gomoku::board::Coordinates;
+        Coordinates { x: $x, y: $y }
+    }};
+}
+
+macro_rules! set_many {
+    (mut $board: expr, $player: expr, $( $coordinate: expr ),*) => {{
+        use gomoku::board::Move;
+        $(
+            #[allow(unused_assignments)]
+            {
+                $board.set_move(&RuleSet::default(), &Move {
+                    player:  $player,
+                    coordinates: coord!($coordinate.0, $coordinate.1)
+                });
+            }
+        )*
+    }};
+}
+
+const CENTER: Coordinates = coord!(BOARD_SIZE / 2, BOARD_SIZE / 2);
+
+// * Statistics
+
+#[test]
+fn play_reports_statistics() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    set_many!(mut board, Player::White, (CENTER.x + 1, CENTER.y));
+    let mut computer = Computer::default();
+    let evaluation = computer
+        .play(
+            Algorithm::Negamax,
+            &RuleSet::default(),
+            &mut board,
+            2,
+            Player::Black,
+        )
+        .unwrap();
+    let statistics = &evaluation.statistics;
+    assert_eq!(statistics.depth, 2);
+    assert_eq!(statistics.max_depth, 2);
+    assert!(statistics.nodes > 1);
+    assert!(statistics.leaf_evaluations > 0);
+    assert!(statistics.leaf_evaluations < statistics.nodes);
+    assert!(statistics.cutoffs > 0);
+    assert_eq!(statistics.iterations.len(), 1);
+    assert!(statistics.effective_branching_factor() > 1.);
+    assert_eq!(computer.statistics.nodes, statistics.nodes);
+}
+
+#[test]
+fn play_resets_statistics() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    let mut computer = Computer::default();
+    let first = computer
+        .play(
+            Algorithm::Minimax,
+            &RuleSet::default(),
+            &mut board,
+            2,
+            Player::White,
+        )
+        .unwrap();
+    let second = computer
+        .play(
+            Algorithm::Minimax,
+            &RuleSet::default(),
+            &mut board,
+            2,
+            Player::White,
+        )
+        .unwrap();
+    assert_eq!(first.statistics.nodes, second.statistics.nodes);
+    assert_eq!(second.statistics.iterations.len(), 1);
+}
+
+// * Cancellation
+
+#[test]
+fn stopped_search_still_returns_a_move() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    set_many!(mut board, Player::White, (CENTER.x + 1, CENTER.y));
+    let mut computer = Computer::default();
+    computer.control.stop();
+    let evaluation = computer
+        .play(
+            Algorithm::Negamax,
+            &RuleSet::default(),
+            &mut board,
+            6,
+            Player::Black,
+        )
+        .unwrap();
+    assert!(!evaluation.movements.is_empty());
+    assert_eq!(
+        computer.control.best().unwrap().movements,
+        evaluation.movements
+    );
+}
+
+#[test]
+fn background_play_returns_the_computer() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    let expected = Computer::default()
+        .play(
+            Algorithm::Minimax,
+            &RuleSet::default(),
+            &mut board.clone(),
+            2,
+            Player::White,
+        )
+        .unwrap();
+    let thinking = Computer::default().play_in_background(
+        Algorithm::Minimax,
+        RuleSet::default(),
+        board,
+        2,
+        Player::White,
+    );
+    let (computer, play_result) = thinking.join();
+    let evaluation = play_result.unwrap();
+    assert_eq!(evaluation.movements, expected.movements);
+    assert_eq!(computer.statistics.nodes, expected.statistics.nodes);
+    assert!(!computer.control.is_stopped());
+}
+
+#[test]
+fn background_play_move_now() {
+    let mut board = Board::default();
+    set_many!(
+        mut board,
+        Player::Black,
+        (CENTER.x, CENTER.y),
+        (CENTER.x + 1, CENTER.y + 1)
+    );
+    set_many!(
+        mut board,
+        Player::White,
+        (CENTER.x + 1, CENTER.y),
+        (CENTER.x, CENTER.y + 1)
+    );
+    let thinking = Computer::default().play_in_background(
+        Algorithm::Negamax,
+        RuleSet::default(),
+        board,
+        8,
+        Player::Black,
+    );
+    thinking.move_now();
+    let (_, play_result) = thinking.join();
+    assert!(!play_result.unwrap().movements.is_empty());
+}
+
+// * Move ordering
+
+#[test]
+fn sorted_move_order_break_ties() {
+    let sorted_move = |best_pattern: u8, order: u32| SortedMove {
+        movement: Move {
+            player: Player::Black,
+            coordinates: CENTER,
+        },
+        pattern_count: PatternCount::default(),
+        best_pattern,
+        order,
+    };
+    assert!(sorted_move(10, 0) > sorted_move(5, 100));
+    assert!(sorted_move(10, 100) > sorted_move(10, 0));
+    assert!(sorted_move(10, 0) == sorted_move(10, 0));
+}
+
+fn play_with_ordering(ordering: MoveOrdering) -> Computer {
+    let mut board = Board::default();
+    set_many!(
+        mut board,
+        Player::Black,
+        (CENTER.x, CENTER.y),
+        (CENTER.x + 1, CENTER.y + 1)
+    );
+    set_many!(
+        mut board,
+        Player::White,
+        (CENTER.x + 1, CENTER.y),
+        (CENTER.x, CENTER.y + 1)
+    );
+    let mut computer = Computer {
+        options: SearchOptions {
+            ordering,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    computer
+        .play(
+            Algorithm::Negamax,
+            &RuleSet::default(),
+            &mut board,
+            3,
+            Player::Black,
+        )
+        .unwrap();
+    computer
+}
+
+#[test]
+fn patterns_ordering_has_no_killers_or_history() {
+    let computer = play_with_ordering(MoveOrdering::Patterns);
+    assert!(computer.statistics.cutoffs > 0);
+    assert!(computer.killers.is_empty());
+    assert!(computer.history.is_empty());
+}
+
+#[test]
+fn killers_and_history_are_stored_on_cutoffs() {
+    let computer = play_with_ordering(MoveOrdering::KillersAndHistory);
+    assert!(computer.statistics.cutoffs > 0);
+    assert!(computer.killers.iter().any(|killers| killers[0].is_some()));
+    assert!(computer
+        .history
+        .iter()
+        .any(|history| history.iter().flatten().any(|score| *score > 0)));
+}
+
+// * Candidates pruning
+
+fn play_with_options(board: &mut Board, options: SearchOptions, player: Player) -> Computer {
+    let mut computer = Computer {
+        options,
+        ..Default::default()
+    };
+    computer
+        .play(Algorithm::Negamax, &RuleSet::default(), board, 2, player)
+        .unwrap();
+    computer
+}
+
+#[test]
+fn beam_width_reduce_visited_nodes() {
+    let mut board = Board::default();
+    set_many!(
+        mut board,
+        Player::Black,
+        (CENTER.x, CENTER.y),
+        (CENTER.x + 1, CENTER.y + 1)
+    );
+    set_many!(
+        mut board,
+        Player::White,
+        (CENTER.x + 1, CENTER.y),
+        (CENTER.x, CENTER.y + 1)
+    );
+    let all = play_with_options(&mut board, SearchOptions::default(), Player::Black);
+    let beam = play_with_options(
+        &mut board,
+        SearchOptions {
+            beam_width: 3,
+            ..Default::default()
+        },
+        Player::Black,
+    );
+    assert!(beam.statistics.nodes < all.statistics.nodes);
+    // Root, 3 children and 3 grand-children for each of them at most
+    assert!(beam.statistics.nodes <= 1 + 3 + 3 * 3);
+}
+
+#[test]
+fn radius_increase_visited_nodes() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    let one = play_with_options(&mut board, SearchOptions::default(), Player::White);
+    let two = play_with_options(
+        &mut board,
+        SearchOptions {
+            radius: 2,
+            ordering: MoveOrdering::Patterns,
+            ..Default::default()
+        },
+        Player::White,
+    );
+    assert!(two.statistics.nodes > one.statistics.nodes);
+}
+
+#[test]
+fn threats_only_must_block_a_four() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (4, 9), (12, 12), (13, 12));
+    set_many!(mut board, Player::White, (5, 9), (6, 9), (7, 9), (8, 9));
+    let computer = play_with_options(
+        &mut board,
+        SearchOptions {
+            threats_only: true,
+            ..Default::default()
+        },
+        Player::Black,
+    );
+    // Only the block is searched at the root
+    let best = computer.control.best().unwrap();
+    assert_eq!(best.movements[0].coordinates, coord!(9, 9));
+    assert!(computer.statistics.nodes < 40);
+}
+
+// * Principal variation search
+
+#[test]
+fn principal_variation_search_iterates_each_depth() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    set_many!(mut board, Player::White, (CENTER.x + 1, CENTER.y));
+    let mut computer = Computer::default();
+    let evaluation = computer
+        .play(
+            Algorithm::PrincipalVariation,
+            &RuleSet::default(),
+            &mut board,
+            3,
+            Player::Black,
+        )
+        .unwrap();
+    let depths: Vec<usize> = evaluation
+        .statistics
+        .iterations
+        .iter()
+        .map(|(depth, _)| *depth)
+        .collect();
+    assert_eq!(depths, vec![1, 2, 3]);
+    assert_eq!(evaluation.statistics.depth, 3);
+    assert_eq!(evaluation.movements.len(), 3);
+}
+
+#[test]
+fn principal_variation_search_agree_with_negamax() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (4, 9), (12, 12), (13, 12));
+    set_many!(mut board, Player::White, (5, 9), (6, 9), (7, 9), (8, 9));
+    let negamax = Computer::default()
+        .play(
+            Algorithm::Negamax,
+            &RuleSet::default(),
+            &mut board,
+            2,
+            Player::Black,
+        )
+        .unwrap();
+    let principal_variation = Computer::default()
+        .play(
+            Algorithm::PrincipalVariation,
+            &RuleSet::default(),
+            &mut board,
+            2,
+            Player::Black,
+        )
+        .unwrap();
+    assert_eq!(principal_variation.score, negamax.score);
+    assert_eq!(principal_variation.movements[0], negamax.movements[0]);
+}
+
+#[test]
+fn stopped_principal_variation_search_still_returns_a_move() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    let mut computer = Computer::default();
+    computer.control.stop();
+    let evaluation = computer
+        .play(
+            Algorithm::PrincipalVariation,
+            &RuleSet::default(),
+            &mut board,
+            4,
+            Player::White,
+        )
+        .unwrap();
+    assert!(!evaluation.movements.is_empty());
+    assert_eq!(evaluation.statistics.iterations.len(), 1);
+}
+
+// * Monte Carlo tree search
+
+#[test]
+fn algorithm_from_name() {
+    assert_eq!("mcts".parse::<Algorithm>(), Ok(Algorithm::MonteCarlo));
+    assert_eq!(
+        "PVS".parse::<Algorithm>(),
+        Ok(Algorithm::PrincipalVariation)
+    );
+    assert_eq!("negamax".parse::<Algorithm>(), Ok(Algorithm::Negamax));
+    assert!("alphazero".parse::<Algorithm>().is_err());
+}
+
+#[test]
+fn monte_carlo_returns_line_with_visits() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    set_many!(mut board, Player::White, (CENTER.x + 1, CENTER.y));
+    let mut computer = Computer::default();
+    let evaluation = computer
+        .play(
+            Algorithm::MonteCarlo,
+            &RuleSet::default(),
+            &mut board,
+            1,
+            Player::Black,
+        )
+        .unwrap();
+    assert!(!evaluation.movements.is_empty());
+    assert_eq!(evaluation.movements.len(), evaluation.visits.len());
+    assert_eq!(evaluation.movements[0].player, Player::Black);
+    // Visits can only decrease along the line
+    assert!(evaluation.visits.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(evaluation.statistics.leaf_evaluations > 0);
+    assert!(evaluation.score >= 0 && evaluation.score <= 1000);
+    // The board is restored after the search
+    assert_eq!(board.moves, 2);
+    assert_eq!(board.all_rocks.len(), 2);
+}
+
+#[test]
+fn monte_carlo_plays_the_winning_move() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (5, 9), (6, 9), (7, 9), (8, 9));
+    set_many!(mut board, Player::White, (4, 9), (12, 12), (13, 12));
+    let evaluation = Computer::default()
+        .play(
+            Algorithm::MonteCarlo,
+            &RuleSet::default(),
+            &mut board,
+            1,
+            Player::Black,
+        )
+        .unwrap();
+    assert_eq!(evaluation.movements[0].coordinates, coord!(9, 9));
+    assert_eq!(evaluation.score, 1000);
+}
+
+#[test]
+fn stopped_monte_carlo_still_returns_a_move() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    let mut computer = Computer::default();
+    computer.control.stop();
+    let evaluation = computer
+        .play(
+            Algorithm::MonteCarlo,
+            &RuleSet::default(),
+            &mut board,
+            4,
+            Player::White,
+        )
+        .unwrap();
+    assert_eq!(evaluation.movements.len(), 1);
+    assert_eq!(evaluation.visits, vec![1]);
+}
+
+// * Mate distance
+
+#[test]
+fn mate_in_from_score() {
+    assert_eq!(mate_in(WIN_SCORE - 1), Some(1));
+    assert_eq!(mate_in(WIN_SCORE - 3), Some(2));
+    assert_eq!(mate_in(-(WIN_SCORE - 2)), Some(-1));
+    assert_eq!(mate_in(-(WIN_SCORE - 4)), Some(-2));
+    assert_eq!(mate_in(50001), None);
+    assert_eq!(mate_in(-50001), None);
+}
+
+#[test]
+fn search_prefers_the_fastest_win() {
+    for algorithm in [
+        Algorithm::Negamax,
+        Algorithm::Minimax,
+        Algorithm::PrincipalVariation,
+    ] {
+        let mut board = Board::default();
+        set_many!(mut board, Player::Black, (5, 9), (6, 9), (7, 9), (8, 9));
+        set_many!(mut board, Player::White, (4, 9), (12, 12), (13, 12));
+        let evaluation = Computer::default()
+            .play(algorithm, &RuleSet::default(), &mut board, 3, Player::Black)
+            .unwrap();
+        assert_eq!(evaluation.movements[0].coordinates, coord!(9, 9));
+        assert_eq!(evaluation.score, WIN_SCORE - 1);
+        assert_eq!(evaluation.mate_in(), Some(1));
+    }
+}
+
+#[test]
+fn search_scores_a_forced_loss() {
+    for algorithm in [
+        Algorithm::Negamax,
+        Algorithm::Minimax,
+        Algorithm::PrincipalVariation,
+    ] {
+        let mut board = Board::default();
+        set_many!(mut board, Player::White, (5, 9), (6, 9), (7, 9), (8, 9));
+        set_many!(mut board, Player::White, (5, 3), (6, 3), (7, 3), (8, 3));
+        set_many!(mut board, Player::Black, (15, 15), (16, 16), (15, 16));
+        let evaluation = Computer::default()
+            .play(algorithm, &RuleSet::default(), &mut board, 2, Player::Black)
+            .unwrap();
+        assert_eq!(evaluation.score, -(WIN_SCORE - 2));
+        assert_eq!(evaluation.mate_in(), Some(-1));
+    }
+}
+
+// * Draw
+
+#[test]
+fn search_scores_the_move_limit_as_a_draw() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (5, 9), (6, 9), (7, 9));
+    set_many!(mut board, Player::White, (12, 12), (13, 12), (14, 12));
+    let rules = RuleSet {
+        move_limit: Some(7),
+        ..Default::default()
+    };
+    for algorithm in [
+        Algorithm::Negamax,
+        Algorithm::Minimax,
+        Algorithm::PrincipalVariation,
+    ] {
+        let evaluation = Computer::default()
+            .play(algorithm, &rules, &mut board, 3, Player::Black)
+            .unwrap();
+        // Every line ends on the limit after the first move
+        assert_eq!(evaluation.score, 0);
+        assert_eq!(evaluation.movements.len(), 1);
+    }
+}
+
+// * Analysis
+
+#[test]
+fn analyse_scores_every_legal_move() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (5, 9), (6, 9), (7, 9), (8, 9));
+    set_many!(mut board, Player::White, (4, 9), (12, 12), (13, 12));
+    let rules = RuleSet::default();
+    let legal_moves = board.intersections_legal_moves(&rules, Player::Black);
+    let analysis = Computer::default()
+        .analyse(Algorithm::Negamax, &rules, &mut board, 3, Player::Black)
+        .unwrap();
+    assert_eq!(analysis.len(), legal_moves.len());
+    for evaluation in &analysis {
+        assert!(legal_moves.contains(&evaluation.movements[0]));
+    }
+    for pair in analysis.windows(2) {
+        assert!(pair[0].score >= pair[1].score);
+    }
+    assert_eq!(analysis[0].movements[0].coordinates, coord!(9, 9));
+    assert_eq!(analysis[0].mate_in(), Some(1));
+    assert!(analysis[1].mate_in().is_none());
+    // The board is restored after the analysis
+    assert_eq!(
+        board.intersections_legal_moves(&rules, Player::Black),
+        legal_moves
+    );
+}
+
+#[test]
+fn analyse_continues_each_move_with_the_reply() {
+    let mut board = Board::default();
+    set_many!(mut board, Player::White, (5, 9), (6, 9), (7, 9), (8, 9));
+    set_many!(mut board, Player::White, (5, 3), (6, 3), (7, 3), (8, 3));
+    set_many!(mut board, Player::Black, (15, 15), (16, 16), (15, 16));
+    let analysis = Computer::default()
+        .analyse(
+            Algorithm::Negamax,
+            &RuleSet::default(),
+            &mut board,
+            2,
+            Player::Black,
+        )
+        .unwrap();
+    assert!(!analysis.is_empty());
+    for evaluation in &analysis {
+        assert_eq!(evaluation.mate_in(), Some(-1));
+        assert_eq!(evaluation.movements[0].player, Player::Black);
+        assert_eq!(evaluation.movements[1].player, Player::White);
+    }
+}
+
+// * Strength
+
+#[test]
+fn strength_levels_grow_stronger() {
+    for level in 1..STRENGTH_LEVELS {
+        let (weaker, stronger) = (Strength::level(level), Strength::level(level + 1));
+        assert!(weaker.depth <= stronger.depth);
+        assert!(weaker.time <= stronger.time);
+        assert!(weaker.beam_width <= stronger.beam_width);
+        let chance = |strength: Strength| strength.randomness.map_or(0., |random| random.chance);
+        assert!(chance(weaker) >= chance(stronger));
+    }
+    assert_eq!(Strength::level(0), Strength::level(1));
+    assert_eq!(Strength::level(99), Strength::level(STRENGTH_LEVELS));
+    assert_eq!(Strength::level(STRENGTH_LEVELS).randomness, None);
+}
+
+#[test]
+fn randomize_chooses_among_the_best_candidates() {
+    let rules = RuleSet::default();
+    let mut board = Board::default();
+    set_many!(
+        mut board,
+        Player::Black,
+        (CENTER.x, CENTER.y),
+        (CENTER.x + 1, CENTER.y + 1)
+    );
+    set_many!(mut board, Player::White, (CENTER.x + 1, CENTER.y));
+    let mut computer = Computer {
+        randomness: Some(Randomness {
+            chance: 1.,
+            candidates: 5,
+            temperature: 1e9,
+        }),
+        ..Default::default()
+    };
+    let evaluation = computer
+        .play(Algorithm::Negamax, &rules, &mut board, 2, Player::White)
+        .unwrap();
+    let statistics = computer.statistics.clone();
+    let legal_moves = board.intersections_legal_moves(&rules, Player::White);
+    let candidates: Vec<Move> = computer
+        .root_candidates(&rules, &board, Player::White)
+        .iter()
+        .take(5)
+        .map(|candidate| candidate.movement)
+        .collect();
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut played = vec![];
+    for _ in 0..20 {
+        let choice = computer
+            .randomize(
+                &rules,
+                &mut board,
+                Player::White,
+                evaluation.clone(),
+                &mut rng,
+            )
+            .unwrap();
+        assert!(candidates.contains(&choice.movements[0]));
+        assert_eq!(choice.statistics.nodes, statistics.nodes);
+        if !played.contains(&choice.movements[0]) {
+            played.push(choice.movements[0]);
+        }
+    }
+    assert!(played.len() > 1);
+    // The board and the statistics of the search are kept
+    assert_eq!(computer.statistics.nodes, statistics.nodes);
+    assert_eq!(board.get(CENTER.x, CENTER.y), Rock::Black);
+    assert_eq!(
+        board.intersections_legal_moves(&rules, Player::White),
+        legal_moves
+    );
+}
+
+#[test]
+fn randomize_keeps_forced_wins() {
+    let rules = RuleSet::default();
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (5, 9), (6, 9), (7, 9), (8, 9));
+    set_many!(mut board, Player::White, (4, 9), (12, 12), (13, 12));
+    let mut computer = Computer {
+        randomness: Some(Randomness {
+            chance: 1.,
+            candidates: 5,
+            temperature: 1e9,
+        }),
+        ..Default::default()
+    };
+    let evaluation = computer
+        .play(Algorithm::Negamax, &rules, &mut board, 2, Player::Black)
+        .unwrap();
+    assert_eq!(evaluation.mate_in(), Some(1));
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..10 {
+        let choice = computer
+            .randomize(
+                &rules,
+                &mut board,
+                Player::Black,
+                evaluation.clone(),
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(choice.movements, evaluation.movements);
+    }
+}
+
+#[test]
+fn randomize_keeps_the_move_of_a_stopped_search() {
+    let rules = RuleSet::default();
+    let mut board = Board::default();
+    set_many!(mut board, Player::Black, (CENTER.x, CENTER.y));
+    let mut computer = Computer {
+        randomness: Some(Randomness {
+            chance: 1.,
+            candidates: 5,
+            temperature: 1e9,
+        }),
+        ..Default::default()
+    };
+    let evaluation = computer
+        .play(Algorithm::Negamax, &rules, &mut board, 2, Player::White)
+        .unwrap();
+    // The time of the search was used before the randomization
+    computer.control.stop();
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..10 {
+        let choice = computer
+            .randomize(
+                &rules,
+                &mut board,
+                Player::White,
+                evaluation.clone(),
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(choice.movements, evaluation.movements);
+    }
+}
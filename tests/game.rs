@@ -0,0 +1,262 @@
+use gomoku::{
+    board::{Coordinates, DrawReason, Move, WinReason},
+    computer::{Algorithm, Evaluation},
+    constants::WIN_SCORE,
+    game::{EngineOutcome, Game, GameMode, Winner},
+    json::Json,
+    player::Player,
+    rock::Rock,
+};
+
+// PvA game where the computer plays white and black already played in the center
+fn game_against_computer() -> Game {
+    let mut game = Game::default();
+    game.start_pva(Rock::Black);
+    game.play_player(Coordinates { x: 9, y: 9 });
+    game
+}
+
+// Search result of the computer for white, with the given score
+fn evaluation(score: i32) -> Result<Evaluation, String> {
+    Ok(Evaluation {
+        score,
+        movements: vec![Move {
+            player: Player::White,
+            coordinates: Coordinates { x: 8, y: 8 },
+        }],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn five_in_a_row_win() {
+    let mut game = Game::default();
+    game.start(GameMode::PvP);
+    for x in 0..5 {
+        game.play_player(Coordinates { x, y: 0 });
+        if x < 4 {
+            game.play_player(Coordinates { x, y: 5 });
+        }
+    }
+    assert_eq!(game.winner, Winner::Black(WinReason::FiveInARow));
+    assert_eq!(game.winner.player(), Some(Player::Black));
+}
+
+#[test]
+fn resign() {
+    let mut game = Game::default();
+    game.start(GameMode::PvP);
+    game.play_player(Coordinates { x: 9, y: 9 });
+    game.resign();
+    assert_eq!(game.winner, Winner::Black(WinReason::Resignation));
+}
+
+#[test]
+fn resign_during_the_computer_turn() {
+    let mut game = game_against_computer();
+    assert_eq!(game.current_player, Player::White);
+    game.play_computer_in_background();
+    game.resign();
+    assert_eq!(game.winner, Winner::White(WinReason::Resignation));
+    assert!(game.computer_thinking.is_none());
+}
+
+#[test]
+fn draw_offer_between_players() {
+    let mut game = Game::default();
+    game.start(GameMode::PvP);
+    game.offer_draw();
+    assert_eq!(game.draw_offer, Some(Player::Black));
+    game.answer_draw(false);
+    assert_eq!(game.draw_offer, None);
+    assert!(game.draw_declined);
+
+    // The offer ends with the turn
+    game.offer_draw();
+    game.play_player(Coordinates { x: 9, y: 9 });
+    assert_eq!(game.draw_offer, None);
+    assert!(!game.draw_declined);
+
+    game.offer_draw();
+    assert_eq!(game.draw_offer, Some(Player::White));
+    game.answer_draw(true);
+    assert_eq!(game.winner, Winner::Draw(DrawReason::Agreement));
+}
+
+#[test]
+fn computer_answers_draw_offers() {
+    let mut game = game_against_computer();
+    game.apply_computer_play(evaluation(350));
+    assert_eq!(game.computer_score, Some(350));
+    assert!(!game.computer_accepts_draw());
+    game.offer_draw();
+    assert!(game.draw_declined);
+    assert_eq!(game.winner, Winner::None);
+
+    game.computer_score = Some(-120);
+    game.offer_draw();
+    assert_eq!(game.winner, Winner::Draw(DrawReason::Agreement));
+}
+
+#[test]
+fn monte_carlo_answers_draw_offers_from_its_win_rate() {
+    let mut game = game_against_computer();
+    game.algorithm_index = Algorithm::ALL
+        .iter()
+        .position(|algorithm| *algorithm == Algorithm::MonteCarlo);
+    game.apply_computer_play(evaluation(650));
+    assert!(!game.computer_accepts_draw());
+    game.offer_draw();
+    assert!(game.draw_declined);
+
+    game.computer_score = Some(420);
+    game.offer_draw();
+    assert_eq!(game.winner, Winner::Draw(DrawReason::Agreement));
+}
+
+#[test]
+fn computer_resigns_a_proven_loss() {
+    let mut game = game_against_computer();
+    game.apply_computer_play(evaluation(-(WIN_SCORE - 4)));
+    assert_eq!(game.winner, Winner::Black(WinReason::Resignation));
+    assert_eq!(game.rock_move.len(), 1);
+
+    // Or plays on when it's disabled
+    let mut game = game_against_computer();
+    game.computer_resigns = false;
+    game.apply_computer_play(evaluation(-(WIN_SCORE - 4)));
+    assert_eq!(game.winner, Winner::None);
+    assert_eq!(game.rock_move.len(), 2);
+}
+
+#[test]
+fn monte_carlo_resigns_when_it_never_wins() {
+    let monte_carlo = Algorithm::ALL
+        .iter()
+        .position(|algorithm| *algorithm == Algorithm::MonteCarlo);
+    let mut game = game_against_computer();
+    game.algorithm_index = monte_carlo;
+    game.apply_computer_play(evaluation(300));
+    assert_eq!(game.winner, Winner::None);
+    assert_eq!(game.rock_move.len(), 2);
+
+    let mut game = game_against_computer();
+    game.algorithm_index = monte_carlo;
+    game.apply_computer_play(evaluation(5));
+    assert_eq!(game.winner, Winner::Black(WinReason::Resignation));
+    assert_eq!(game.rock_move.len(), 1);
+}
+
+#[test]
+fn engine_log_lines() {
+    let path = std::env::temp_dir().join(format!("gomoku_{}_engine.jsonl", std::process::id()));
+    let mut game = game_against_computer();
+    game.engine_log = true;
+    game.engine_log_path = path.to_string_lossy().to_string();
+    game.apply_computer_play(evaluation(350));
+    game.play_player(Coordinates { x: 10, y: 10 });
+    game.apply_computer_play(evaluation(-(WIN_SCORE - 4)));
+
+    let log = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let entries: Vec<Json> = log.lines().map(|line| line.parse().unwrap()).collect();
+    assert_eq!(entries.len(), 2);
+    let entry = &entries[0];
+    assert_eq!(entry.get("move_number").and_then(Json::as_i64), Some(2));
+    assert_eq!(entry.get("player").and_then(Json::as_str), Some("white"));
+    assert_eq!(entry.get("score").and_then(Json::as_i64), Some(350));
+    assert_eq!(
+        entry
+            .get("played")
+            .and_then(|played| played.get("x"))
+            .and_then(Json::as_i64),
+        Some(8)
+    );
+    // The position is logged before the move of the computer
+    let Some(Json::Array(moves)) = entry.get("moves") else {
+        panic!("missing moves");
+    };
+    assert_eq!(moves.len(), 1);
+    let Some(Json::Array(candidates)) = entry.get("candidates") else {
+        panic!("missing candidates");
+    };
+    assert!(!candidates.is_empty());
+    assert!(candidates[0].get("patterns").is_some());
+    assert!(entry
+        .get("statistics")
+        .and_then(|statistics| statistics.get("nodes"))
+        .is_some());
+
+    assert_eq!(entry.get("resigned"), Some(&Json::Bool(false)));
+    assert_eq!(entries[1].get("played"), Some(&Json::Null));
+    assert_eq!(entries[1].get("resigned"), Some(&Json::Bool(true)));
+    assert_eq!(entries[1].get("no_move"), Some(&Json::Bool(false)));
+
+    // A draw without legal move is not a resignation
+    let entry = game.engine_log_entry(EngineOutcome::NoMove, std::time::Duration::ZERO);
+    assert_eq!(entry.get("played"), Some(&Json::Null));
+    assert_eq!(entry.get("resigned"), Some(&Json::Bool(false)));
+    assert_eq!(entry.get("no_move"), Some(&Json::Bool(true)));
+}
+
+// Poll the background analysis until the position is analysed or a few seconds passed
+fn wait_analysis(game: &mut Game) {
+    let started = std::time::Instant::now();
+    while game.analysis_position.as_ref() != Some(&game.rock_move)
+        && started.elapsed() < std::time::Duration::from_secs(5)
+    {
+        game.analyse_position();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn analysis_runs_in_the_background() {
+    let mut game = Game::default();
+    game.start(GameMode::PvP);
+    game.play_player(Coordinates { x: 9, y: 9 });
+    game.analyse_position();
+    // Nothing is analysed until the background search is done
+    assert!(game.analysing.is_some());
+    assert_eq!(game.analysis_position, None);
+    wait_analysis(&mut game);
+    assert_eq!(game.analysis_position.as_ref(), Some(&game.rock_move));
+    assert!(!game.analysis.is_empty());
+    assert!(game
+        .analysis
+        .iter()
+        .all(|evaluation| evaluation.movements[0].player == Player::White));
+
+    // A new position replaces the analysis in progress
+    game.play_player(Coordinates { x: 8, y: 8 });
+    game.analyse_position();
+    game.play_player(Coordinates { x: 10, y: 10 });
+    wait_analysis(&mut game);
+    assert_eq!(game.analysis_position.as_ref(), Some(&game.rock_move));
+    assert!(game
+        .analysis
+        .iter()
+        .all(|evaluation| evaluation.movements[0].player == Player::White));
+}
+
+#[test]
+fn out_of_range_settings_are_dropped() {
+    let path = std::env::temp_dir().join(format!("gomoku_{}_settings.txt", std::process::id()));
+    std::fs::write(
+        &path,
+        "capture: true\ngame_ending_capture: true\nno_double_three: true\nmode: PvP\nalgorithm: 99\nradius: 500\nordering: 2\nstrength: 10\n",
+    )
+    .unwrap();
+    let mut game = Game::default();
+    let loaded = game.load(&path.to_string_lossy());
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded, Ok(()));
+    let default = Game::default();
+    assert_eq!(game.algorithm_index, default.algorithm_index);
+    assert_eq!(game.radius_index, default.radius_index);
+    assert_eq!(game.strength_index, default.strength_index);
+    assert_eq!(game.ordering_index, Some(2));
+
+    game.algorithm_index = Some(99);
+    assert_eq!(game.algorithm(), Algorithm::Negamax);
+}
@@ -0,0 +1,204 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use gomoku::{
+    board::Coordinates,
+    game::{Game, Winner},
+    network::{Message, NetworkGame, OpeningChoice, PROTOCOL_VERSION},
+    rules::RuleSet,
+};
+
+// Poll the network game until it received the messages, an error, or a second passed
+fn wait_messages(network: &NetworkGame, count: usize) -> (Vec<Message>, Option<String>) {
+    let started = Instant::now();
+    let mut received = vec![];
+    loop {
+        let (messages, error) = network.receive();
+        received.extend(messages);
+        if received.len() >= count || error.is_some() || started.elapsed() > Duration::from_secs(1)
+        {
+            return (received, error);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn connected_games() -> (NetworkGame, NetworkGame) {
+    let mut host = NetworkGame::host("127.0.0.1:0");
+    let address = host.local_address().unwrap();
+    let joined = NetworkGame::join(&address);
+    assert_eq!(joined.error, None);
+    let started = Instant::now();
+    while !host.accept() {
+        assert!(started.elapsed() < Duration::from_secs(1));
+        thread::sleep(Duration::from_millis(10));
+    }
+    (host, joined)
+}
+
+#[test]
+fn messages_round_trip() {
+    let messages = vec![
+        Message::Hello {
+            version: PROTOCOL_VERSION,
+            rules: RuleSet {
+                capture: false,
+                move_limit: Some(200),
+                ..Default::default()
+            },
+            opening: 1,
+            time_control: 3,
+        },
+        Message::Move(Coordinates { x: 9, y: 10 }),
+        Message::Choice(OpeningChoice::PlaceStones),
+        Message::UndoRequest,
+        Message::UndoAccept,
+        Message::UndoDecline,
+        Message::Resign,
+        Message::DrawOffer,
+        Message::DrawAccept,
+        Message::DrawDecline,
+        Message::Chat("good game".to_string()),
+    ];
+    for message in messages {
+        assert_eq!(message.to_string().parse::<Message>(), Ok(message));
+    }
+    assert_eq!(
+        Message::Chat("two\nlines".to_string()).to_string(),
+        "chat two lines"
+    );
+}
+
+#[test]
+fn invalid_messages() {
+    assert!("move 9".parse::<Message>().is_err());
+    assert!("choice red".parse::<Message>().is_err());
+    assert!("hello 1 true true".parse::<Message>().is_err());
+    assert!("undo please".parse::<Message>().is_err());
+    assert!("checkmate".parse::<Message>().is_err());
+}
+
+#[test]
+fn play_on_loopback() {
+    let (mut host, mut joined) = connected_games();
+    assert!(host.is_connected() && joined.is_connected());
+    assert!(host.is_host && !joined.is_host);
+
+    host.send(Message::Move(Coordinates { x: 9, y: 9 }));
+    host.send(Message::Chat("your turn".to_string()));
+    let (messages, error) = wait_messages(&joined, 2);
+    assert_eq!(error, None);
+    assert_eq!(
+        messages,
+        vec![
+            Message::Move(Coordinates { x: 9, y: 9 }),
+            Message::Chat("your turn".to_string())
+        ]
+    );
+
+    joined.send(Message::DrawOffer);
+    assert_eq!(wait_messages(&host, 1), (vec![Message::DrawOffer], None));
+}
+
+#[test]
+fn messages_before_the_disconnection_are_received() {
+    let (mut host, joined) = connected_games();
+    host.send(Message::Resign);
+    drop(host);
+    thread::sleep(Duration::from_millis(50));
+    let (messages, error) = joined.receive();
+    assert_eq!(messages, vec![Message::Resign]);
+    assert!(error.is_some());
+}
+
+#[test]
+fn join_without_host() {
+    let host = NetworkGame::host("127.0.0.1:0");
+    let address = host.local_address().unwrap();
+    drop(host);
+    let joined = NetworkGame::join(&address);
+    assert!(!joined.is_connected());
+    assert!(joined.error.is_some());
+}
+
+// Game hosted by this instance, against an opponent that sends raw messages
+fn hosted_game() -> (Game, NetworkGame) {
+    let mut game = Game {
+        network_address: "127.0.0.1:0".to_string(),
+        ..Default::default()
+    };
+    game.host_network_game();
+    let address = game.network.as_ref().unwrap().local_address().unwrap();
+    let opponent = NetworkGame::join(&address);
+    let started = Instant::now();
+    while !game.playing {
+        assert!(started.elapsed() < Duration::from_secs(5));
+        game.update_network();
+        thread::sleep(Duration::from_millis(5));
+    }
+    let (messages, error) = wait_messages(&opponent, 1);
+    assert!(matches!(messages[..], [Message::Hello { .. }]) && error.is_none());
+    (game, opponent)
+}
+
+// Apply the messages of the opponent until the connection is closed
+fn wait_disconnection(game: &mut Game) {
+    let started = Instant::now();
+    while game.network.as_ref().unwrap().is_connected() {
+        assert!(started.elapsed() < Duration::from_secs(5));
+        game.update_network();
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(game.network.as_ref().unwrap().error.is_some());
+}
+
+#[test]
+fn illegal_moves_close_the_connection() {
+    let center = Coordinates { x: 9, y: 9 };
+    let played = |coordinates: Option<Coordinates>, opponent_move: Coordinates| {
+        let (mut game, mut opponent) = hosted_game();
+        if let Some(coordinates) = coordinates {
+            game.play_local_move(coordinates);
+        }
+        let moves = game.rock_move.clone();
+        opponent.send(Message::Move(opponent_move));
+        wait_disconnection(&mut game);
+        assert_eq!(game.rock_move, moves);
+        game
+    };
+    // Out of turn, the host plays black
+    let game = played(None, center);
+    assert!(game.rock_move.is_empty());
+    // Outside of the board
+    played(Some(center), Coordinates { x: 30, y: 30 });
+    played(Some(center), Coordinates { x: -1, y: 0 });
+    // On an occupied intersection
+    let game = played(Some(center), center);
+    assert_eq!(game.rock_move.len(), 1);
+}
+
+#[test]
+fn answers_without_request_close_the_connection() {
+    for answer in [Message::DrawAccept, Message::UndoAccept] {
+        let (mut game, mut opponent) = hosted_game();
+        game.play_local_move(Coordinates { x: 9, y: 9 });
+        opponent.send(answer);
+        wait_disconnection(&mut game);
+        assert_eq!(game.winner, Winner::None);
+        assert_eq!(game.rock_move.len(), 1);
+    }
+
+    // The draw offered by this instance is accepted
+    let (mut game, mut opponent) = hosted_game();
+    game.offer_draw();
+    opponent.send(Message::DrawAccept);
+    let started = Instant::now();
+    while game.winner == Winner::None {
+        assert!(started.elapsed() < Duration::from_secs(5));
+        game.update_network();
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(game.network.as_ref().unwrap().is_connected());
+}
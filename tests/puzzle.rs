@@ -0,0 +1,196 @@
+use gomoku::{
+    computer::{Algorithm, SearchOptions},
+    player::Player,
+    puzzle::{parse_coordinates, Puzzle, PUZZLES},
+    rock::Rock,
+};
+
+macro_rules! coord {
+    ($x: expr, $y: expr) => {{
+        use gomoku::board::Coordinates;
+        Coordinates { x: $x, y: $y }
+    }};
+}
+
+// * Parsing
+
+#[test]
+fn parse_puzzle() {
+    let puzzle = Puzzle::parse(
+        "name: Simple\n\
+         to_move: white\n\
+         rules: capture\n\
+         black: 9x9 10x9\n\
+         white: 8x9\n\
+         white_captures: 4\n\
+         best: 11x9\n\
+         avoid: 0x0",
+    )
+    .unwrap();
+    assert_eq!(puzzle.name, "Simple");
+    assert_eq!(puzzle.to_move, Player::White);
+    assert!(puzzle.rules.capture);
+    assert!(!puzzle.rules.game_ending_capture);
+    assert!(!puzzle.rules.no_double_three);
+    assert_eq!(puzzle.black, vec![coord!(9, 9), coord!(10, 9)]);
+    assert_eq!(puzzle.white, vec![coord!(8, 9)]);
+    assert_eq!(puzzle.black_captures, 0);
+    assert_eq!(puzzle.white_captures, 4);
+    assert_eq!(puzzle.best, vec![coord!(11, 9)]);
+    assert_eq!(puzzle.avoid, vec![coord!(0, 0)]);
+}
+
+#[test]
+fn parse_puzzle_missing_side_to_move() {
+    assert!(Puzzle::parse("name: Simple\nbest: 9x9").is_err());
+}
+
+#[test]
+fn parse_puzzle_without_solution() {
+    assert!(Puzzle::parse("name: Simple\nto_move: black").is_err());
+}
+
+#[test]
+fn parse_puzzle_unknown_key() {
+    assert!(Puzzle::parse("name: Simple\nto_move: black\nbest: 9x9\nfoo: bar").is_err());
+}
+
+#[test]
+fn parse_puzzle_with_both_colors_on_a_coordinate() {
+    assert!(
+        Puzzle::parse("name: Simple\nto_move: black\nblack: 8x8 9x9\nwhite: 9x9\nbest: 7x7")
+            .is_err()
+    );
+}
+
+#[test]
+fn parse_coordinates_out_of_board() {
+    assert!(parse_coordinates("19x0").is_err());
+    assert!(parse_coordinates("-1x0").is_err());
+    assert!(parse_coordinates("9-9").is_err());
+    assert_eq!(parse_coordinates("18x0").unwrap(), coord!(18, 0));
+}
+
+#[test]
+fn parse_suite_with_comments() {
+    let puzzles = Puzzle::parse_suite(
+        "# Comment only block\n\
+         \n\
+         name: First\n\
+         to_move: black\n\
+         best: 9x9\n\
+         \n\
+         \n\
+         # Second puzzle\n\
+         name: Second\n\
+         to_move: white\n\
+         avoid: 9x9\n",
+    )
+    .unwrap();
+    assert_eq!(puzzles.len(), 2);
+    assert_eq!(puzzles[0].name, "First");
+    assert_eq!(puzzles[1].name, "Second");
+}
+
+#[test]
+fn default_suite_is_valid() {
+    assert!(!PUZZLES.is_empty());
+}
+
+// * Board
+
+#[test]
+fn puzzle_board_has_rocks_and_captures() {
+    let puzzle = Puzzle::parse(
+        "name: Board\n\
+         to_move: black\n\
+         rules: capture\n\
+         black: 6x9\n\
+         white: 7x9 8x9\n\
+         black_captures: 8\n\
+         best: 9x9",
+    )
+    .unwrap();
+    let board = puzzle.board();
+    assert_eq!(board.get(6, 9), Rock::Black);
+    assert_eq!(board.get(7, 9), Rock::White);
+    assert_eq!(board.get(8, 9), Rock::White);
+    assert_eq!(board.black.captures, 8);
+    assert_eq!(board.white.captures, 0);
+    assert_eq!(board.moves, 3);
+}
+
+#[test]
+fn puzzle_is_solved_by() {
+    let puzzle = Puzzle::parse("name: Avoid\nto_move: black\navoid: 9x9").unwrap();
+    assert!(!puzzle.is_solved_by(&coord!(9, 9)));
+    assert!(puzzle.is_solved_by(&coord!(9, 10)));
+    let puzzle = Puzzle::parse("name: Best\nto_move: black\nbest: 9x9").unwrap();
+    assert!(puzzle.is_solved_by(&coord!(9, 9)));
+    assert!(!puzzle.is_solved_by(&coord!(9, 10)));
+}
+
+// * Solve
+
+fn solve(name: &str, algorithm: Algorithm, depth: usize) -> bool {
+    let puzzle = PUZZLES.iter().find(|puzzle| puzzle.name == name).unwrap();
+    let result = puzzle.run(algorithm, depth, SearchOptions::default());
+    assert!(result.nodes > 0);
+    result.solved
+}
+
+#[test]
+fn solve_win_in_one() {
+    assert!(solve("Complete an open four", Algorithm::Negamax, 2));
+    assert!(solve("Complete an open four", Algorithm::Minimax, 2));
+    assert!(solve("Complete an open four", Algorithm::Greedy, 2));
+    assert!(solve(
+        "Complete an open four",
+        Algorithm::PrincipalVariation,
+        2
+    ));
+    assert!(solve("Complete a broken four", Algorithm::Negamax, 2));
+}
+
+#[test]
+fn solve_capture_win() {
+    assert!(solve("Tenth captured stone", Algorithm::Negamax, 2));
+    assert!(solve("Tenth captured stone", Algorithm::Minimax, 2));
+    assert!(solve("Tenth captured stone", Algorithm::MonteCarlo, 1));
+    assert!(solve("Capture that breaks a five", Algorithm::Negamax, 2));
+}
+
+#[test]
+fn solve_must_block() {
+    assert!(solve("Block a closed four", Algorithm::Negamax, 2));
+    assert!(solve("Block a broken four", Algorithm::Negamax, 2));
+    assert!(solve("Block a capture win", Algorithm::Negamax, 2));
+}
+
+#[test]
+fn solve_open_three_without_capture() {
+    assert!(solve(
+        "Block an open three without the capture rule",
+        Algorithm::Negamax,
+        2
+    ));
+}
+
+#[test]
+fn solve_continuous_fours() {
+    assert!(solve("Four then double four", Algorithm::Negamax, 2));
+    assert!(solve("Broken double four", Algorithm::Negamax, 2));
+    assert!(solve(
+        "Broken double four",
+        Algorithm::PrincipalVariation,
+        2
+    ));
+}
+
+#[test]
+fn solve_every_puzzle_of_the_suite() {
+    for puzzle in PUZZLES.iter() {
+        let result = puzzle.run(Algorithm::Negamax, 2, SearchOptions::default());
+        assert!(result.solved, "{} is not solved", puzzle.name);
+    }
+}
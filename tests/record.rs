@@ -0,0 +1,125 @@
+use gomoku::{
+    board::Move, game::Game, player::Player, record::GameRecord, rules::RuleSet,
+    variation::VariationTree,
+};
+
+macro_rules! movement {
+    ($player: expr, $x: expr, $y: expr) => {{
+        use gomoku::board::Coordinates;
+        Move {
+            player: $player,
+            coordinates: Coordinates { x: $x, y: $y },
+        }
+    }};
+}
+
+fn tree_with_variations() -> VariationTree {
+    let mut tree = VariationTree::default();
+    tree.play(movement!(Player::Black, 9, 9));
+    tree.play(movement!(Player::White, 10, 10));
+    tree.play(movement!(Player::Black, 8, 8));
+    tree.back();
+    tree.back();
+    tree.play(movement!(Player::White, 7, 7));
+    tree.play(movement!(Player::Black, 6, 6));
+    tree.back();
+    tree
+}
+
+#[test]
+fn record_round_trip() {
+    let mut record = GameRecord {
+        rules: RuleSet {
+            capture: false,
+            move_limit: Some(200),
+            ..Default::default()
+        },
+        variations: tree_with_variations(),
+        ..Default::default()
+    };
+    record.set("mode", "PvA");
+    record.set("player_place_stones", 2);
+    record.set("completed_opening", true);
+
+    let loaded: GameRecord = record.to_string().parse().unwrap();
+    assert_eq!(loaded.rules, record.rules);
+    assert_eq!(loaded.get::<String>("mode"), Some("PvA".to_string()));
+    assert_eq!(loaded.get::<usize>("player_place_stones"), Some(2));
+    assert_eq!(loaded.get::<bool>("completed_opening"), Some(true));
+    assert_eq!(loaded.get::<bool>("ask_player_choice"), None);
+    assert_eq!(loaded.variations.to_string(), record.variations.to_string());
+    assert_eq!(
+        loaded.variations.line(loaded.variations.current),
+        record.variations.line(record.variations.current)
+    );
+    assert_eq!(
+        loaded.variations.next_moves(),
+        vec![movement!(Player::Black, 6, 6)]
+    );
+}
+
+#[test]
+fn record_moves_setting() {
+    let mut record = GameRecord::default();
+    let moves = vec![
+        movement!(Player::White, 10, 10),
+        movement!(Player::Black, 8, 8),
+    ];
+    record.set_moves("undone", &moves);
+    record.set_moves("empty", &[]);
+    let loaded: GameRecord = record.to_string().parse().unwrap();
+    assert_eq!(loaded.get_moves("undone"), Some(moves));
+    assert_eq!(loaded.get_moves("empty"), Some(vec![]));
+    assert_eq!(loaded.get_moves("missing"), None);
+}
+
+#[test]
+fn invalid_record() {
+    assert!("capture".parse::<GameRecord>().is_err());
+    assert!("capture: maybe".parse::<GameRecord>().is_err());
+    assert!("variation: 0 B 9x9".parse::<GameRecord>().is_err());
+    assert!("variation: - X 9x9".parse::<GameRecord>().is_err());
+    assert!("variation: - B 30x30".parse::<GameRecord>().is_err());
+    assert!("variation: - B -1x9".parse::<GameRecord>().is_err());
+    assert!("undone: W 19x0"
+        .parse::<GameRecord>()
+        .unwrap()
+        .get_moves("undone")
+        .is_none());
+    assert!("variation: - B 9x9\ncurrent: 1"
+        .parse::<GameRecord>()
+        .is_err());
+    assert!("variation: - B 9x9\nvariation: - B 9x9"
+        .parse::<GameRecord>()
+        .is_err());
+}
+
+#[test]
+fn illegal_variations_are_not_replayed() {
+    let mut game = Game::default();
+    assert_eq!(
+        game.load_record(RuleSet::default(), tree_with_variations()),
+        Ok(())
+    );
+    assert_eq!(game.replay_length(), 3);
+
+    // A variation plays again on an occupied intersection
+    let mut tree = tree_with_variations();
+    tree.play(movement!(Player::Black, 9, 9));
+    let mut game = Game::default();
+    assert!(game.load_record(RuleSet::default(), tree).is_err());
+    assert_eq!(game.replay_length(), 0);
+
+    // A saved game modified to play twice on the center
+    let path = std::env::temp_dir().join(format!("gomoku_{}_illegal.txt", std::process::id()));
+    std::fs::write(
+        &path,
+        "mode: PvP\nvariation: - B 9x9\nvariation: 0 W 9x9\ncurrent: 1\n",
+    )
+    .unwrap();
+    let loaded = game.load(&path.to_string_lossy());
+    let _ = std::fs::remove_file(&path);
+    assert!(loaded.is_err());
+    assert!(!game.playing);
+    assert!(game.rock_move.is_empty());
+}
@@ -0,0 +1,82 @@
+use gomoku::{
+    board::Move,
+    computer::{Algorithm, Computer},
+    constants::WIN_SCORE,
+    player::Player,
+    review::{review_game, MoveQuality},
+    rules::RuleSet,
+};
+
+macro_rules! movement {
+    ($player: expr, $x: expr, $y: expr) => {{
+        use gomoku::board::Coordinates;
+        Move {
+            player: $player,
+            coordinates: Coordinates { x: $x, y: $y },
+        }
+    }};
+}
+
+#[test]
+fn classify_by_lost_score() {
+    assert_eq!(MoveQuality::classify(30000, 30000), MoveQuality::Best);
+    assert_eq!(MoveQuality::classify(31000, 30000), MoveQuality::Best);
+    assert_eq!(MoveQuality::classify(29000, 30000), MoveQuality::Good);
+    assert_eq!(MoveQuality::classify(10000, 30000), MoveQuality::Inaccuracy);
+    assert_eq!(MoveQuality::classify(-5000, 30000), MoveQuality::Blunder);
+}
+
+#[test]
+fn classify_forced_wins_and_losses() {
+    assert_eq!(
+        MoveQuality::classify(30000, WIN_SCORE - 1),
+        MoveQuality::MissedWin
+    );
+    assert_eq!(
+        MoveQuality::classify(WIN_SCORE - 3, WIN_SCORE - 1),
+        MoveQuality::Good
+    );
+    assert_eq!(
+        MoveQuality::classify(-(WIN_SCORE - 2), 500),
+        MoveQuality::Blunder
+    );
+    assert_eq!(
+        MoveQuality::classify(-(WIN_SCORE - 2), -(WIN_SCORE - 4)),
+        MoveQuality::Good
+    );
+}
+
+#[test]
+fn review_finds_the_missed_win() {
+    let moves = vec![
+        movement!(Player::Black, 5, 9),
+        movement!(Player::White, 12, 12),
+        movement!(Player::Black, 6, 9),
+        movement!(Player::White, 12, 14),
+        movement!(Player::Black, 7, 9),
+        movement!(Player::White, 4, 9),
+        movement!(Player::Black, 8, 9),
+        movement!(Player::White, 14, 12),
+        movement!(Player::Black, 3, 3),
+    ];
+    let reviews = review_game(
+        &mut Computer::default(),
+        Algorithm::Negamax,
+        &RuleSet::default(),
+        &moves,
+        2,
+    )
+    .unwrap();
+    assert_eq!(reviews.len(), moves.len());
+    for (review, movement) in reviews.iter().zip(moves.iter()) {
+        assert_eq!(review.movement, *movement);
+        assert!((-1. ..=1.).contains(&review.black_advantage()));
+    }
+    // White had to block the four
+    assert_eq!(reviews[7].quality, MoveQuality::Blunder);
+    assert_eq!(reviews[7].best_move, movement!(Player::White, 9, 9));
+    let last = &reviews[8];
+    assert_eq!(last.quality, MoveQuality::MissedWin);
+    assert_eq!(last.best_move, movement!(Player::Black, 9, 9));
+    assert_eq!(last.to_string(), "missed win, best 9x9");
+}
@@ -0,0 +1,101 @@
+use gomoku::{
+    board::{Board, Coordinates, Move},
+    computer::{Algorithm, Computer},
+    json::Json,
+    player::Player,
+    rules::RuleSet,
+    search_tree::{explore, SearchTree},
+};
+
+macro_rules! movement {
+    ($x: expr, $y: expr, $player: expr) => {
+        Move {
+            player: $player,
+            coordinates: Coordinates { x: $x, y: $y },
+        }
+    };
+}
+
+// Search of white after two moves, recorded up to the limit
+fn recorded_search(algorithm: Algorithm, limit: usize) -> (i32, SearchTree) {
+    let rules = RuleSet::default();
+    let mut board = Board::default();
+    board.set_move(&rules, &movement!(9, 9, Player::Black));
+    board.set_move(&rules, &movement!(10, 9, Player::White));
+    board.set_move(&rules, &movement!(9, 10, Player::Black));
+    let mut computer = Computer {
+        tree: Some(SearchTree::new(limit)),
+        ..Default::default()
+    };
+    let evaluation = computer
+        .play(algorithm, &rules, &mut board, 2, Player::White)
+        .unwrap();
+    (evaluation.score, computer.tree.unwrap())
+}
+
+#[test]
+fn record_negamax_search() {
+    let (score, tree) = recorded_search(Algorithm::Negamax, 100_000);
+    assert!(!tree.truncated);
+    let root = &tree.nodes[0];
+    assert_eq!(root.movement, None);
+    assert_eq!(root.score, Some(score));
+    assert!(!root.children.is_empty());
+    for node in &tree.nodes[1..] {
+        assert!(node.ply <= 2);
+        assert!(node.patterns.is_some());
+        assert!(node.score.is_some());
+        let parent = &tree.nodes[node.parent.unwrap()];
+        assert_eq!(node.ply, parent.ply + 1);
+        assert_ne!(
+            node.movement.unwrap().player,
+            parent
+                .movement
+                .map_or(Player::Black, |movement| movement.player)
+        );
+    }
+    // The cutoffs are recorded on the node where they happened
+    assert!(tree.nodes.iter().any(|node| node.cutoff.is_some()));
+    assert_eq!(tree.subtree_size(0), tree.nodes.len());
+    let leaf = tree.nodes.iter().position(|node| node.ply == 2).unwrap();
+    assert_eq!(tree.path(leaf).len(), 2);
+}
+
+#[test]
+fn node_limit() {
+    let (score, full) = recorded_search(Algorithm::Minimax, 100_000);
+    let (limited_score, tree) = recorded_search(Algorithm::Minimax, 10);
+    // Recording doesn't change the search
+    assert_eq!(limited_score, score);
+    assert!(!full.truncated);
+    assert!(tree.truncated);
+    assert_eq!(tree.nodes.len(), 10);
+    assert_eq!(tree.nodes[0].score, Some(score));
+}
+
+#[test]
+fn json_round_trip() {
+    let (_, tree) = recorded_search(Algorithm::Negamax, 200);
+    let text = tree.to_json().to_string();
+    let json = text.parse::<Json>().unwrap();
+    assert_eq!(SearchTree::from_json(&json), Ok(tree));
+
+    assert!(SearchTree::from_json(&Json::Null).is_err());
+    let orphan = r#"{"nodes":[{"move":null,"parent":null,"alpha":0,"beta":1},{"move":null,"parent":3,"alpha":0,"beta":1}]}"#;
+    assert!(SearchTree::from_json(&orphan.parse().unwrap()).is_err());
+}
+
+#[test]
+fn explore_the_tree() {
+    let (_, tree) = recorded_search(Algorithm::Negamax, 1000);
+    let first = tree.nodes[tree.nodes[0].children[0]].movement.unwrap();
+    let mut output = vec![];
+    explore(&tree, "1\nu\n999\n1\nr\nq\n1\n".as_bytes(), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with(&format!("{} nodes\nroot\n", tree.nodes.len())));
+    assert!(output.contains(&format!("\n{}\nply 1 window", first)));
+    assert!(output.contains("unknown command 999"));
+    // The input after q is ignored
+    assert_eq!(output.matches("ply 1 window").count(), 2);
+    assert_eq!(output.matches("\nroot\n").count(), 3);
+}
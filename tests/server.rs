@@ -0,0 +1,227 @@
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+use gomoku::{
+    json::Json,
+    server::{read_request, Request, Server, DEFAULT_SEARCH_TIME, MAX_SEARCH_TIME},
+};
+
+fn handle(server: &mut Server, method: &str, path: &str, body: &str) -> (u16, Json) {
+    let response = server.handle(&Request::new(method, path, body));
+    (response.status, response.body.unwrap_or(Json::Null))
+}
+
+#[test]
+fn json_round_trip() {
+    let text = r#"{"a":[1,-2.5,true,null],"b":{"c":"line\n\"quoted\" é"},"d":[]}"#;
+    let json = text.parse::<Json>().unwrap();
+    assert_eq!(
+        json.get("a"),
+        Some(&Json::Array(vec![
+            Json::Number(1.),
+            Json::Number(-2.5),
+            Json::Bool(true),
+            Json::Null
+        ]))
+    );
+    assert_eq!(
+        json.get("b")
+            .and_then(|b| b.get("c"))
+            .and_then(Json::as_str),
+        Some("line\n\"quoted\" é")
+    );
+    assert_eq!(json.to_string().parse::<Json>(), Ok(json));
+    assert!(r#"{"a":1,}"#.parse::<Json>().is_err());
+    assert!("[1 2]".parse::<Json>().is_err());
+    assert!("true false".parse::<Json>().is_err());
+    // The nesting is limited
+    assert!(format!("{}{}", "[".repeat(64), "]".repeat(64))
+        .parse::<Json>()
+        .is_ok());
+    assert!(format!("{}{}", "[".repeat(65), "]".repeat(65))
+        .parse::<Json>()
+        .is_err());
+}
+
+#[test]
+fn create_and_play() {
+    let mut server = Server::new();
+    let (status, game) = handle(&mut server, "POST", "/games", r#"{"capture":false}"#);
+    assert_eq!(status, 201);
+    assert_eq!(game.get("id").and_then(Json::as_i64), Some(1));
+    let rules = game.get("rules").unwrap();
+    assert_eq!(rules.get("capture"), Some(&Json::Bool(false)));
+    assert_eq!(rules.get("game_ending_capture"), Some(&Json::Bool(false)));
+
+    let (status, game) = handle(&mut server, "POST", "/games/1/moves", r#"{"x":9,"y":9}"#);
+    assert_eq!(status, 200);
+    assert_eq!(
+        game.get("current_player").and_then(Json::as_str),
+        Some("white")
+    );
+    let Some(Json::Array(rows)) = game.get("board") else {
+        panic!("missing board");
+    };
+    assert_eq!(rows[9].as_str(), Some(".........B........."));
+
+    // The same intersection is refused with its reason
+    let (status, error) = handle(&mut server, "POST", "/games/1/moves", r#"{"x":9,"y":9}"#);
+    assert_eq!(status, 422);
+    assert!(error.get("reason").is_some());
+    let (status, _) = handle(&mut server, "POST", "/games/1/moves", r#"{"x":19,"y":0}"#);
+    assert_eq!(status, 422);
+
+    let (status, result) = handle(&mut server, "POST", "/games/1/computer", r#"{"depth":2}"#);
+    assert_eq!(status, 200);
+    assert_eq!(
+        result
+            .get("move")
+            .and_then(|movement| movement.get("player"))
+            .and_then(Json::as_str),
+        Some("white")
+    );
+    let Some(Json::Array(moves)) = result.get("game").and_then(|game| game.get("moves")) else {
+        panic!("missing moves");
+    };
+    assert_eq!(moves.len(), 2);
+
+    let (status, legal) = handle(&mut server, "GET", "/games/1/legal_moves", "");
+    assert_eq!(status, 200);
+    let Some(Json::Array(moves)) = legal.get("moves") else {
+        panic!("missing moves");
+    };
+    assert_eq!(moves.len(), 19 * 19 - 2);
+}
+
+#[test]
+fn search_time_is_limited() {
+    let mut server = Server::new();
+    handle(&mut server, "POST", "/games", "");
+    let time = |result: &Json| result.get("time_ms").and_then(Json::as_i64);
+
+    // A deep search without time stops after the default time
+    let started = Instant::now();
+    let (status, result) = handle(&mut server, "POST", "/games/1/computer", r#"{"depth":10}"#);
+    assert_eq!(status, 200);
+    assert!(started.elapsed() < DEFAULT_SEARCH_TIME + Duration::from_secs(2));
+    assert!(result
+        .get("move")
+        .is_some_and(|movement| *movement != Json::Null));
+    assert_eq!(time(&result), Some(DEFAULT_SEARCH_TIME.as_millis() as i64));
+
+    // A longer time is reduced to the maximum
+    let (status, result) = handle(
+        &mut server,
+        "POST",
+        "/games/1/computer",
+        r#"{"depth":1,"time_ms":999999999999}"#,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(time(&result), Some(MAX_SEARCH_TIME.as_millis() as i64));
+}
+
+#[test]
+fn errors() {
+    let mut server = Server::new();
+    assert_eq!(handle(&mut server, "GET", "/games/1", "").0, 404);
+    assert_eq!(handle(&mut server, "POST", "/games", "{").0, 400);
+    assert_eq!(
+        handle(&mut server, "POST", "/games", &"[".repeat(60_000)).0,
+        400
+    );
+    assert_eq!(
+        handle(&mut server, "POST", "/games", r#"{"algorithm":"chess"}"#).0,
+        400
+    );
+    assert_eq!(
+        handle(&mut server, "POST", "/games", r#"{"capture":1}"#).0,
+        400
+    );
+    assert_eq!(handle(&mut server, "PUT", "/games", "").0, 405);
+    assert_eq!(handle(&mut server, "POST", "/games", "").0, 201);
+    assert_eq!(handle(&mut server, "PATCH", "/games/1", "").0, 405);
+    assert_eq!(handle(&mut server, "GET", "/games/1/unknown", "").0, 404);
+    assert_eq!(
+        handle(&mut server, "POST", "/games/1/moves", r#"{"x":"9"}"#).0,
+        400
+    );
+    assert_eq!(
+        handle(&mut server, "POST", "/games/1/computer", r#"{"depth":99}"#).0,
+        400
+    );
+    assert_eq!(handle(&mut server, "DELETE", "/games/1", "").0, 204);
+    assert_eq!(
+        handle(&mut server, "GET", "/games", "").1.to_string(),
+        r#"{"games":[]}"#
+    );
+}
+
+#[test]
+fn resign_and_draw() {
+    let mut server = Server::new();
+    handle(&mut server, "POST", "/games", "");
+    // Without an evaluation the computer declines the draw
+    let (status, result) = handle(&mut server, "POST", "/games/1/draw", "");
+    assert_eq!(status, 200);
+    assert_eq!(result.get("accepted"), Some(&Json::Bool(false)));
+
+    let (status, game) = handle(
+        &mut server,
+        "POST",
+        "/games/1/resign",
+        r#"{"player":"white"}"#,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(game.get("winner").and_then(Json::as_str), Some("black"));
+    assert_eq!(
+        game.get("reason").and_then(Json::as_str),
+        Some("by resignation")
+    );
+    assert_eq!(handle(&mut server, "POST", "/games/1/resign", "").0, 409);
+    assert_eq!(handle(&mut server, "POST", "/games/1/draw", "").0, 409);
+}
+
+#[test]
+fn read_request_limits() {
+    let request = "POST /games HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}";
+    assert_eq!(
+        read_request(request.as_bytes()),
+        Ok(Request::new("POST", "/games", "{}"))
+    );
+    let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(10_000));
+    assert!(read_request(long_line.as_bytes()).is_err());
+    let many_headers = format!("GET /games HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(100));
+    assert!(read_request(many_headers.as_bytes()).is_err());
+    // A request that stops before the end of its body is refused
+    assert!(
+        read_request("POST /games HTTP/1.1\r\nContent-Length: 9\r\n\r\n{}".as_bytes()).is_err()
+    );
+}
+
+#[test]
+fn serve_on_loopback() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || Server::new().serve(listener));
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    let request = r#"{"algorithm":"greedy"}"#;
+    write!(
+        stream,
+        "POST /games HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\n\r\n{}",
+        address,
+        request.len(),
+        request
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 201 Created\r\n"));
+    let (_, json) = response.split_once("\r\n\r\n").unwrap();
+    let json = json.parse::<Json>().unwrap();
+    assert_eq!(json.get("id").and_then(Json::as_i64), Some(1));
+}
@@ -0,0 +1,108 @@
+use gomoku::{board::Move, player::Player, variation::VariationTree};
+
+macro_rules! movement {
+    ($player: expr, $x: expr, $y: expr) => {{
+        use gomoku::board::Coordinates;
+        Move {
+            player: $player,
+            coordinates: Coordinates { x: $x, y: $y },
+        }
+    }};
+}
+
+fn main_line_tree() -> VariationTree {
+    let mut tree = VariationTree::default();
+    tree.play(movement!(Player::Black, 9, 9));
+    tree.play(movement!(Player::White, 10, 10));
+    tree.play(movement!(Player::Black, 8, 8));
+    tree
+}
+
+#[test]
+fn play_follows_the_existing_move() {
+    let mut tree = main_line_tree();
+    assert_eq!(tree.back(), Some(movement!(Player::Black, 8, 8)));
+    tree.play(movement!(Player::Black, 8, 8));
+    assert_eq!(tree.nodes.len(), 3);
+    assert_eq!(tree.current, Some(2));
+}
+
+#[test]
+fn back_before_the_first_move() {
+    let mut tree = main_line_tree();
+    tree.rewind();
+    assert_eq!(tree.back(), None);
+    assert_eq!(tree.current, None);
+    assert_eq!(tree.next_moves(), vec![movement!(Player::Black, 9, 9)]);
+}
+
+#[test]
+fn new_move_keeps_the_old_continuation() {
+    let mut tree = main_line_tree();
+    tree.back();
+    tree.back();
+    tree.play(movement!(Player::White, 7, 7));
+    assert_eq!(
+        tree.line(tree.current),
+        vec![
+            movement!(Player::Black, 9, 9),
+            movement!(Player::White, 7, 7)
+        ]
+    );
+    assert!(!tree.is_main_line());
+    tree.back();
+    assert_eq!(
+        tree.next_moves(),
+        vec![
+            movement!(Player::White, 10, 10),
+            movement!(Player::White, 7, 7)
+        ]
+    );
+    assert_eq!(
+        tree.continuation(tree.current),
+        vec![
+            movement!(Player::White, 10, 10),
+            movement!(Player::Black, 8, 8)
+        ]
+    );
+}
+
+#[test]
+fn promote_variation_to_main_line() {
+    let mut tree = main_line_tree();
+    tree.back();
+    tree.back();
+    tree.play(movement!(Player::White, 7, 7));
+    tree.play(movement!(Player::Black, 6, 6));
+    tree.promote();
+    assert!(tree.is_main_line());
+    assert_eq!(
+        tree.continuation(None),
+        vec![
+            movement!(Player::Black, 9, 9),
+            movement!(Player::White, 7, 7),
+            movement!(Player::Black, 6, 6)
+        ]
+    );
+}
+
+#[test]
+fn display_variations_in_parentheses() {
+    let mut tree = main_line_tree();
+    assert_eq!(tree.to_string(), "1. B 9x9 2. W 10x10 3. B 8x8");
+    tree.back();
+    tree.back();
+    tree.play(movement!(Player::White, 7, 7));
+    tree.play(movement!(Player::Black, 6, 6));
+    tree.back();
+    tree.play(movement!(Player::Black, 5, 5));
+    assert_eq!(
+        tree.to_string(),
+        "1. B 9x9 2. W 10x10 (2. W 7x7 3. B 6x6 (3. B 5x5)) 3. B 8x8"
+    );
+}
+
+#[test]
+fn display_empty_tree() {
+    assert_eq!(VariationTree::default().to_string(), "");
+}